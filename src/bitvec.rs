@@ -0,0 +1,294 @@
+use crate::{order::MonomialOrdering, poly::Polynomial, ring::RingRef, sbox::Sbox, var::VarSet};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Not, Shl, Shr};
+
+/// Word of symbolic bits over a ring, least significant bit first.
+#[derive(Clone)]
+pub struct BitVec<T: MonomialOrdering> {
+    ring: RingRef<T>,
+    bits: Vec<Polynomial<T>>,
+}
+
+impl<T: MonomialOrdering> BitVec<T> {
+    pub fn from_bits(ring: &RingRef<T>, bits: Vec<Polynomial<T>>) -> Self {
+        BitVec {
+            ring: ring.clone(),
+            bits,
+        }
+    }
+
+    /// Word whose bit `i` is the ring variable `vars[i]`.
+    pub fn from_vars(ring: &RingRef<T>, vars: &[usize]) -> Self {
+        let bits = vars
+            .iter()
+            .map(|&v| Polynomial::from_variable(ring, ring.var(v)))
+            .collect();
+        BitVec {
+            ring: ring.clone(),
+            bits,
+        }
+    }
+
+    /// The `width` variables starting at `first`.
+    pub fn variables(ring: &RingRef<T>, first: usize, width: usize) -> Self {
+        Self::from_vars(ring, &(first..first + width).collect::<Vec<_>>())
+    }
+
+    pub fn constant(ring: &RingRef<T>, value: u64, width: usize) -> Self {
+        let bits = (0..width)
+            .map(|i| {
+                if i < 64 && value >> i & 1 == 1 {
+                    Polynomial::one(ring)
+                } else {
+                    Polynomial::zero(ring)
+                }
+            })
+            .collect();
+        BitVec {
+            ring: ring.clone(),
+            bits,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    pub fn bit(&self, i: usize) -> &Polynomial<T> {
+        &self.bits[i]
+    }
+
+    pub fn bits(&self) -> &[Polynomial<T>] {
+        &self.bits
+    }
+
+    pub fn into_bits(self) -> Vec<Polynomial<T>> {
+        self.bits
+    }
+
+    /// Value of the word at an assignment, at most 64 bits wide.
+    pub fn eval(&self, assignment: &VarSet) -> u64 {
+        assert!(self.len() <= 64);
+        self.bits
+            .iter()
+            .enumerate()
+            .fold(0, |w, (i, b)| w | (b.eval(assignment) as u64) << i)
+    }
+
+    /// Bit `i` of the result is bit `i - k` of `self`, modulo the width.
+    pub fn rotate_left(&self, k: usize) -> Self {
+        let mut bits = self.bits.clone();
+        if !bits.is_empty() {
+            bits.rotate_right(k % self.len());
+        }
+        BitVec {
+            ring: self.ring.clone(),
+            bits,
+        }
+    }
+
+    pub fn rotate_right(&self, k: usize) -> Self {
+        self.rotate_left(self.len() - k % self.len().max(1))
+    }
+
+    /// Concatenation with `high` as the more significant part.
+    pub fn concat(&self, high: &BitVec<T>) -> Self {
+        let mut bits = self.bits.clone();
+        bits.extend_from_slice(&high.bits);
+        BitVec {
+            ring: self.ring.clone(),
+            bits,
+        }
+    }
+
+    /// Bits `start..end`.
+    pub fn slice(&self, start: usize, end: usize) -> Self {
+        BitVec {
+            ring: self.ring.clone(),
+            bits: self.bits[start..end].to_vec(),
+        }
+    }
+
+    /// Applies the S-box to every chunk of `sbox.input_bits()` bits, lowest
+    /// chunk first.
+    pub fn lookup(&self, sbox: &Sbox) -> Self {
+        let n = sbox.input_bits();
+        assert_eq!(0, self.len() % n);
+        let bits = self
+            .bits
+            .chunks(n)
+            .flat_map(|chunk| sbox.apply(chunk))
+            .collect();
+        BitVec {
+            ring: self.ring.clone(),
+            bits,
+        }
+    }
+
+    fn zip_with(
+        &self,
+        other: &BitVec<T>,
+        f: impl Fn(&Polynomial<T>, &Polynomial<T>) -> Polynomial<T>,
+    ) -> Self {
+        assert_eq!(self.len(), other.len());
+        let bits = self
+            .bits
+            .iter()
+            .zip(&other.bits)
+            .map(|(a, b)| f(a, b))
+            .collect();
+        BitVec {
+            ring: self.ring.clone(),
+            bits,
+        }
+    }
+}
+
+macro_rules! impl_bitwise {
+    ($tr:ident, $f:ident, |$a:ident, $b:ident| $body:expr) => {
+        impl<T: MonomialOrdering> $tr<&BitVec<T>> for &BitVec<T> {
+            type Output = BitVec<T>;
+            fn $f(self, rhs: &BitVec<T>) -> BitVec<T> {
+                self.zip_with(rhs, |$a, $b| $body)
+            }
+        }
+
+        impl<T: MonomialOrdering> $tr for BitVec<T> {
+            type Output = BitVec<T>;
+            fn $f(self, rhs: BitVec<T>) -> BitVec<T> {
+                (&self).$f(&rhs)
+            }
+        }
+    };
+}
+
+impl_bitwise!(BitXor, bitxor, |a, b| a + b);
+impl_bitwise!(BitAnd, bitand, |a, b| a * b);
+impl_bitwise!(BitOr, bitor, |a, b| a + b + a * b);
+
+impl<T: MonomialOrdering> BitVec<T> {
+    /// Sum modulo `2^len`, expanding the carry chain
+    /// `c_{i+1} = a_i b_i + c_i (a_i + b_i)`.
+    pub fn wrapping_add(&self, other: &BitVec<T>) -> Self {
+        assert_eq!(self.len(), other.len());
+        let mut carry = Polynomial::zero(&self.ring);
+        let mut bits = Vec::with_capacity(self.len());
+        for (a, b) in self.bits.iter().zip(&other.bits) {
+            let half = a + b;
+            bits.push(&half + &carry);
+            carry = a * b + &carry * &half;
+        }
+        BitVec {
+            ring: self.ring.clone(),
+            bits,
+        }
+    }
+}
+
+impl<T: MonomialOrdering> Add<&BitVec<T>> for &BitVec<T> {
+    type Output = BitVec<T>;
+    fn add(self, rhs: &BitVec<T>) -> BitVec<T> {
+        self.wrapping_add(rhs)
+    }
+}
+
+impl<T: MonomialOrdering> Add for BitVec<T> {
+    type Output = BitVec<T>;
+    fn add(self, rhs: BitVec<T>) -> BitVec<T> {
+        self.wrapping_add(&rhs)
+    }
+}
+
+impl<T: MonomialOrdering> Not for &BitVec<T> {
+    type Output = BitVec<T>;
+    fn not(self) -> BitVec<T> {
+        BitVec {
+            ring: self.ring.clone(),
+            bits: self.bits.iter().map(|b| b + 1).collect(),
+        }
+    }
+}
+
+impl<T: MonomialOrdering> Not for BitVec<T> {
+    type Output = BitVec<T>;
+    fn not(self) -> BitVec<T> {
+        !&self
+    }
+}
+
+/// Logical shift towards the most significant bit, filling with zeros.
+impl<T: MonomialOrdering> Shl<usize> for &BitVec<T> {
+    type Output = BitVec<T>;
+    fn shl(self, k: usize) -> BitVec<T> {
+        let k = k.min(self.len());
+        let mut bits = vec![Polynomial::zero(&self.ring); k];
+        bits.extend_from_slice(&self.bits);
+        bits.truncate(self.len());
+        BitVec {
+            ring: self.ring.clone(),
+            bits,
+        }
+    }
+}
+
+impl<T: MonomialOrdering> Shr<usize> for &BitVec<T> {
+    type Output = BitVec<T>;
+    fn shr(self, k: usize) -> BitVec<T> {
+        let k = k.min(self.len());
+        let mut bits = self.bits[k..].to_vec();
+        bits.resize(self.len(), Polynomial::zero(&self.ring));
+        BitVec {
+            ring: self.ring.clone(),
+            bits,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BitVec;
+    use crate::{order::DegLex, ring::Ring, sbox::Sbox, testing::assignment};
+
+    #[test]
+    fn word_operations() {
+        let ring = Ring::<DegLex>::new(16).into_ref();
+        let x = BitVec::variables(&ring, 0, 8);
+        let y = BitVec::variables(&ring, 8, 8);
+        let k = BitVec::constant(&ring, 0x5a, 8);
+        let f = &(&(&x + &y) ^ &x.rotate_left(3)) | &(&!&y & &k);
+        let g = &(&x << 2) ^ &(&y >> 3).rotate_right(1);
+        for (a, b) in [
+            (0x00, 0x00),
+            (0xff, 0x01),
+            (0x3c, 0xa5),
+            (0x81, 0x7e),
+            (0x12, 0xef),
+        ] {
+            let point = assignment(16, (a | b << 8) as usize);
+            let expected = ((a + b) & 0xff ^ (a as u8).rotate_left(3) as u64) | (!b & 0x5a);
+            assert_eq!(expected, f.eval(&point));
+            let expected = (a << 2 & 0xff) ^ ((b >> 3) as u8).rotate_right(1) as u64;
+            assert_eq!(expected, g.eval(&point));
+        }
+    }
+
+    #[test]
+    fn sbox_lookup() {
+        const PRESENT: [u64; 16] = [
+            0xC, 0x5, 0x6, 0xB, 0x9, 0x0, 0xA, 0xD, 0x3, 0xE, 0xF, 0x8, 0x4, 0x7, 0x1, 0x2,
+        ];
+        let ring = Ring::<DegLex>::new(16).into_ref();
+        let sbox = Sbox::new(PRESENT.to_vec(), 4);
+        let x = BitVec::variables(&ring, 0, 8);
+        let y = BitVec::variables(&ring, 8, 8);
+        let f = (&x ^ &y).lookup(&sbox);
+        for (a, b) in [(0x00, 0x00), (0x9c, 0x31), (0xf0, 0x0f)] {
+            let z = (a ^ b) as usize;
+            let expected = PRESENT[z & 0xf] | PRESENT[z >> 4] << 4;
+            assert_eq!(expected, f.eval(&assignment(16, (a | b << 8) as usize)));
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::{order::MonomialOrdering, poly::Polynomial, var::VarSet};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+pub struct CnfOptions {
+    /// Longest XOR kept in one piece; longer ones are chained through fresh
+    /// variables. At least 3, and below 64 unless `xor_clauses` is set, as an
+    /// expanded XOR of length `k` takes `2^(k-1)` clauses.
+    pub cutting_length: usize,
+    /// Keep XORs as CryptoMiniSat `x` clauses instead of expanding them into
+    /// `2^(k-1)` plain clauses each.
+    pub xor_clauses: bool,
+}
+
+impl Default for CnfOptions {
+    fn default() -> Self {
+        CnfOptions {
+            cutting_length: 5,
+            xor_clauses: false,
+        }
+    }
+}
+
+/// `vars[0] ^ ... ^ vars[k-1] = rhs` over positive CNF variables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct XorClause {
+    pub vars: Vec<usize>,
+    pub rhs: bool,
+}
+
+/// Clauses over the variables `1..=vars`, DIMACS style. Variable `i + 1`
+/// stands for ring variable `i`, the ones above `gens` are Tseitin variables
+/// of monomials and links of cut XORs.
+pub struct Cnf {
+    pub vars: usize,
+    pub clauses: Vec<Vec<i64>>,
+    pub xors: Vec<XorClause>,
+    gens: usize,
+    /// Ring variables of the monomial each Tseitin variable stands for,
+    /// indexed by `var - gens - 1`; empty for links.
+    products: Vec<Vec<usize>>,
+}
+
+impl Cnf {
+    /// CNF variable of ring variable `v`.
+    pub fn cnf_var(&self, v: usize) -> usize {
+        assert!(v < self.gens);
+        v + 1
+    }
+
+    /// Ring variable of a CNF variable, `None` for auxiliary ones.
+    pub fn ring_var(&self, var: usize) -> Option<usize> {
+        (1..=self.gens).contains(&var).then(|| var - 1)
+    }
+
+    /// Ring variables whose product the CNF variable stands for, `None` for
+    /// ring variables and XOR links.
+    pub fn monomial_of(&self, var: usize) -> Option<&[usize]> {
+        let i = var.checked_sub(self.gens + 1)?;
+        self.products
+            .get(i)
+            .filter(|vars| !vars.is_empty())
+            .map(Vec::as_slice)
+    }
+
+    /// Turns a solver model, given as signed literals, back into an
+    /// assignment of the ring variables.
+    pub fn assignment(&self, model: &[i64]) -> VarSet {
+        let mut a = VarSet::empty(self.gens);
+        for &lit in model.iter().filter(|&&lit| lit > 0) {
+            if let Some(v) = self.ring_var(lit as usize) {
+                a.insert(v);
+            }
+        }
+        a
+    }
+
+    fn fresh(&mut self, product: Vec<usize>) -> usize {
+        self.vars += 1;
+        self.products.push(product);
+        self.vars
+    }
+
+    fn add_xor(&mut self, vars: Vec<usize>, rhs: bool, xor_clauses: bool) {
+        if vars.is_empty() {
+            if rhs {
+                self.clauses.push(Vec::new());
+            }
+        } else if xor_clauses {
+            self.xors.push(XorClause { vars, rhs });
+        } else {
+            // forbid every assignment of the wrong parity
+            let k = vars.len();
+            for bits in 0..1u64 << k {
+                if (bits.count_ones() % 2 == 1) != rhs {
+                    let clause = vars
+                        .iter()
+                        .enumerate()
+                        .map(|(i, &v)| {
+                            if bits >> i & 1 == 1 {
+                                -(v as i64)
+                            } else {
+                                v as i64
+                            }
+                        })
+                        .collect();
+                    self.clauses.push(clause);
+                }
+            }
+        }
+    }
+}
+
+impl Display for Cnf {
+    /// DIMACS; XOR clauses are written in the CryptoMiniSat `x` form, where a
+    /// clause asserts that the XOR of its literals is true.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "p cnf {} {}",
+            self.vars,
+            self.clauses.len() + self.xors.len()
+        )?;
+        for clause in &self.clauses {
+            for lit in clause {
+                write!(f, "{} ", lit)?;
+            }
+            writeln!(f, "0")?;
+        }
+        for xor in &self.xors {
+            write!(f, "x")?;
+            for (i, &v) in xor.vars.iter().enumerate() {
+                let negate = i == 0 && !xor.rhs;
+                write!(f, "{}{} ", if negate { "-" } else { "" }, v)?;
+            }
+            writeln!(f, "0")?;
+        }
+        Ok(())
+    }
+}
+
+/// Encodes the system `polys = 0`.
+///
+/// Every nonlinear monomial gets one Tseitin variable shared by all
+/// polynomials, each polynomial then becomes an XOR of variables.
+pub fn anf_to_cnf<T: MonomialOrdering>(polys: &[Polynomial<T>], options: &CnfOptions) -> Cnf {
+    assert!(options.cutting_length >= 3);
+    assert!(options.xor_clauses || options.cutting_length < 64);
+    let gens = polys.first().map_or(0, |p| p.ring().gens());
+    let mut cnf = Cnf {
+        vars: gens,
+        clauses: Vec::new(),
+        xors: Vec::new(),
+        gens,
+        products: Vec::new(),
+    };
+    let mut tseitin: HashMap<VarSet, usize> = HashMap::new();
+    for p in polys {
+        let mut xor = Vec::new();
+        let mut rhs = false;
+        for m in p.terms() {
+            let Some(vars) = m.vars() else { continue };
+            match vars.len() {
+                0 => rhs = !rhs,
+                1 => xor.push(cnf.cnf_var(vars.iter().next().unwrap())),
+                _ => {
+                    if let Some(&t) = tseitin.get(vars) {
+                        xor.push(t);
+                        continue;
+                    }
+                    let factors: Vec<usize> = vars.iter().map(|v| cnf.cnf_var(v)).collect();
+                    let t = cnf.fresh(vars.iter().collect());
+                    // t -> x_i for all i, and x_1 & ... & x_k -> t
+                    for &x in &factors {
+                        cnf.clauses.push(vec![-(t as i64), x as i64]);
+                    }
+                    let mut long: Vec<i64> = factors.iter().map(|&x| -(x as i64)).collect();
+                    long.push(t as i64);
+                    cnf.clauses.push(long);
+                    tseitin.insert(vars.clone(), t);
+                    xor.push(t);
+                }
+            }
+        }
+        // x_1 ^ ... ^ x_n = rhs becomes x_1 ^ ... ^ x_{l-1} ^ y = 0 and
+        // y ^ x_l ^ ... ^ x_n = rhs
+        while xor.len() > options.cutting_length {
+            let rest = xor.split_off(options.cutting_length - 1);
+            let y = cnf.fresh(Vec::new());
+            xor.push(y);
+            cnf.add_xor(xor, false, options.xor_clauses);
+            xor = rest;
+            xor.insert(0, y);
+        }
+        cnf.add_xor(xor, rhs, options.xor_clauses);
+    }
+    cnf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{anf_to_cnf, Cnf, CnfOptions};
+    use crate::{
+        order::DegLex,
+        ring::Ring,
+        system::is_solution,
+        testing::{assignments, variables},
+        var::VarSet,
+    };
+
+    fn satisfies(cnf: &Cnf, bits: u64) -> bool {
+        let value = |lit: i64| (bits >> (lit.unsigned_abs() - 1) & 1 == 1) == (lit > 0);
+        cnf.clauses.iter().all(|c| c.iter().any(|&lit| value(lit)))
+            && cnf
+                .xors
+                .iter()
+                .all(|x| (x.vars.iter().filter(|&&v| value(v as i64)).count() % 2 == 1) == x.rhs)
+    }
+
+    /// Projections of all models onto the ring variables.
+    fn projected_models(cnf: &Cnf) -> Vec<VarSet> {
+        let mut models: Vec<_> = (0..1u64 << cnf.vars)
+            .filter(|&bits| satisfies(cnf, bits))
+            .map(|bits| {
+                let model: Vec<i64> = (1..=cnf.vars as i64)
+                    .map(|v| if bits >> (v - 1) & 1 == 1 { v } else { -v })
+                    .collect();
+                cnf.assignment(&model)
+            })
+            .collect();
+        models.sort_by(|a, b| a.words().cmp(b.words()));
+        models.dedup();
+        models
+    }
+
+    #[test]
+    fn models_are_solutions() {
+        let n = 5;
+        let ring = Ring::<DegLex>::new(n).into_ref();
+        let x = variables(&ring);
+        let f = [
+            &x[0] * &x[1] + &x[2] + &x[3] + &x[4] + &x[1] * &x[3] + 1,
+            &x[0] * &x[1] + &x[4],
+            &x[2] * &x[3] * &x[4] + &x[0],
+        ];
+        let mut solutions: Vec<_> = assignments(n).filter(|a| is_solution(&f, a)).collect();
+        solutions.sort_by(|a, b| a.words().cmp(b.words()));
+        for xor_clauses in [false, true] {
+            let options = CnfOptions {
+                cutting_length: 3,
+                xor_clauses,
+            };
+            let cnf = anf_to_cnf(&f, &options);
+            // x_1*x_3, x_0*x_1, x_2*x_3*x_4 and two links for the first XOR
+            assert_eq!(n + 5, cnf.vars);
+            assert_eq!(Some(&[1, 3][..]), cnf.monomial_of(n + 1));
+            assert_eq!(None, cnf.ring_var(n + 1));
+            assert_eq!(solutions, projected_models(&cnf));
+        }
+    }
+
+    #[test]
+    fn dimacs() {
+        let ring = Ring::<DegLex>::new(3).into_ref();
+        let x = variables(&ring);
+        let f = [&x[0] * &x[1] + &x[2], &x[0] + &x[2] + 1];
+        let cnf = anf_to_cnf(&f, &CnfOptions::default());
+        assert_eq!(
+            "p cnf 4 7\n-4 1 0\n-4 2 0\n-1 -2 4 0\n-3 4 0\n3 -4 0\n3 1 0\n-3 -1 0\n",
+            cnf.to_string()
+        );
+        let options = CnfOptions {
+            xor_clauses: true,
+            ..Default::default()
+        };
+        let cnf = anf_to_cnf(&f, &options);
+        assert_eq!(
+            "p cnf 4 5\n-4 1 0\n-4 2 0\n-1 -2 4 0\nx-3 4 0\nx3 1 0\n",
+            cnf.to_string()
+        );
+    }
+}
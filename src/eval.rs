@@ -0,0 +1,161 @@
+use crate::{order::MonomialOrdering, poly::Polynomial, var::VarSet};
+use std::ops::{BitAnd, BitOr, BitXor, Not};
+
+/// Bitsliced Boolean values: bit `i` of every word belongs to assignment
+/// `i` of the batch.
+pub trait Lanes:
+    Copy
+    + Eq
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+{
+    const WIDTH: usize;
+    const ZERO: Self;
+
+    fn get(&self, lane: usize) -> bool;
+    fn set(&mut self, lane: usize, value: bool);
+}
+
+macro_rules! impl_lanes {
+    ($t:ty) => {
+        impl Lanes for $t {
+            const WIDTH: usize = <$t>::BITS as usize;
+            const ZERO: Self = 0;
+
+            fn get(&self, lane: usize) -> bool {
+                self >> lane & 1 == 1
+            }
+
+            fn set(&mut self, lane: usize, value: bool) {
+                *self = *self & !(1 << lane) | (value as $t) << lane;
+            }
+        }
+    };
+}
+
+impl_lanes!(u64);
+impl_lanes!(u128);
+
+/// 256 lanes in four `u64` words, lane `i` in word `i / 64`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct U256(pub [u64; 4]);
+
+macro_rules! impl_u256_op {
+    ($tr:ident, $f:ident, $op:tt) => {
+        impl $tr for U256 {
+            type Output = U256;
+            fn $f(self, rhs: U256) -> U256 {
+                let mut words = self.0;
+                for (w, r) in words.iter_mut().zip(rhs.0) {
+                    *w $op r;
+                }
+                U256(words)
+            }
+        }
+    };
+}
+
+impl_u256_op!(BitAnd, bitand, &=);
+impl_u256_op!(BitOr, bitor, |=);
+impl_u256_op!(BitXor, bitxor, ^=);
+
+impl Not for U256 {
+    type Output = U256;
+    fn not(self) -> U256 {
+        U256(self.0.map(|w| !w))
+    }
+}
+
+impl Lanes for U256 {
+    const WIDTH: usize = 256;
+    const ZERO: Self = U256([0; 4]);
+
+    fn get(&self, lane: usize) -> bool {
+        self.0[lane / 64].get(lane % 64)
+    }
+
+    fn set(&mut self, lane: usize, value: bool) {
+        self.0[lane / 64].set(lane % 64, value);
+    }
+}
+
+/// Transposes up to `L::WIDTH` assignments into one lane word per variable,
+/// the input of `Polynomial::eval_batch`.
+pub fn pack_assignments<L: Lanes>(assignments: &[VarSet], gens: usize) -> Vec<L> {
+    assert!(assignments.len() <= L::WIDTH);
+    let mut vars = vec![L::ZERO; gens];
+    for (lane, a) in assignments.iter().enumerate() {
+        for v in a.iter() {
+            vars[v].set(lane, true);
+        }
+    }
+    vars
+}
+
+impl<T: MonomialOrdering> Polynomial<T> {
+    /// Value at the point where exactly the variables in `assignment` are one.
+    pub fn eval(&self, assignment: &VarSet) -> bool {
+        self.terms()
+            .filter(|m| m.vars().is_some_and(|vars| vars.is_subset(assignment)))
+            .count()
+            % 2
+            == 1
+    }
+
+    /// Evaluates `L::WIDTH` assignments at once, `vars[i]` holding the values
+    /// of variable `i` in all of them.
+    pub fn eval_batch<L: Lanes>(&self, vars: &[L]) -> L {
+        debug_assert_eq!(self.ring().gens(), vars.len());
+        let mut acc = L::ZERO;
+        for m in self.terms() {
+            if let Some(m_vars) = m.vars() {
+                acc = acc ^ m_vars.iter().fold(!L::ZERO, |t, v| t & vars[v]);
+            }
+        }
+        acc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pack_assignments, Lanes, U256};
+    use crate::{
+        order::DegLex,
+        ring::Ring,
+        testing::{assignment, assignments, variables},
+    };
+
+    #[test]
+    fn eval() {
+        let ring = Ring::<DegLex>::new(3).into_ref();
+        let x = variables(&ring);
+        let p = &x[0] * &x[1] + &x[2] + 1;
+        assert!(p.eval(&assignment(3, 0b000)));
+        assert!(!p.eval(&assignment(3, 0b100)));
+        assert!(!p.eval(&assignment(3, 0b011)));
+        assert!(p.eval(&assignment(3, 0b111)));
+    }
+
+    fn batch_agrees<L: Lanes>() {
+        let n = 9;
+        let ring = Ring::<DegLex>::new(n).into_ref();
+        let x = variables(&ring);
+        let p = &x[0] * &x[1] * &x[8] + &x[2] * &x[5] + &x[3] + &x[7] * &x[4] + 1;
+        let all: Vec<_> = assignments(n).collect();
+        for chunk in all.chunks(L::WIDTH) {
+            let values: L = p.eval_batch(&pack_assignments(chunk, n));
+            for (lane, a) in chunk.iter().enumerate() {
+                assert_eq!(p.eval(a), values.get(lane));
+            }
+        }
+    }
+
+    #[test]
+    fn eval_batch() {
+        batch_agrees::<u64>();
+        batch_agrees::<u128>();
+        batch_agrees::<U256>();
+    }
+}
@@ -0,0 +1,293 @@
+use crate::{
+    order::MonomialOrdering, poly::Polynomial, ring::RingRef, system::fix_system, var::VarSet,
+};
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+pub struct ExhaustiveOptions {
+    /// The first `prefix_vars` variables are fixed to each of their
+    /// `2^prefix_vars` values in turn, giving independent jobs. Below 64.
+    /// Defaults to about four jobs per thread.
+    pub prefix_vars: Option<usize>,
+    /// Worker threads sharing those jobs.
+    pub threads: usize,
+}
+
+impl Default for ExhaustiveOptions {
+    fn default() -> Self {
+        ExhaustiveOptions {
+            prefix_vars: None,
+            threads: 1,
+        }
+    }
+}
+
+impl ExhaustiveOptions {
+    fn prefix_vars(&self) -> usize {
+        self.prefix_vars.unwrap_or(match self.threads {
+            0 | 1 => 0,
+            threads => threads.next_power_of_two().trailing_zeros() as usize + 2,
+        })
+    }
+}
+
+fn gray(i: u64) -> u64 {
+    i ^ (i >> 1)
+}
+
+/// Position of the set `k_1 < ... < k_t` among all `t`-subsets, the
+/// combinatorial number system `sum C(k_j, j)`.
+fn subset_index(mut s: u64, binom: &[Vec<usize>]) -> usize {
+    let mut index = 0;
+    let mut j = 1;
+    while s != 0 {
+        index += binom[s.trailing_zeros() as usize][j];
+        s &= s - 1;
+        j += 1;
+    }
+    index
+}
+
+/// All zeros of `polys` in the variables `free`, every other variable being
+/// absent from `polys`. Solutions are returned as masks over `free`.
+///
+/// The first 64 polynomials are bitsliced into one word and walked through
+/// the Gray code with the derivative tables of Bouillaguet et al.: for the
+/// lowest set bits `k_1 < ... < k_t` of the step counter, `d[k_1..k_t]`
+/// holds `∂_{k_1}...∂_{k_t} f` at the previous point. Candidates are then
+/// checked against the remaining polynomials.
+fn gray_search<T: MonomialOrdering>(polys: &[Polynomial<T>], free: &[usize]) -> Vec<u64> {
+    let nf = free.len();
+    assert!(nf < 64, "too many variables for exhaustive search");
+    let (sliced, rest) = polys.split_at(polys.len().min(64));
+    let mut position = vec![usize::MAX; free.iter().max().map_or(0, |&v| v + 1)];
+    for (f, &v) in free.iter().enumerate() {
+        position[v] = f;
+    }
+    let masks: Vec<Vec<u64>> = sliced
+        .iter()
+        .map(|p| {
+            p.terms()
+                .filter_map(|m| m.vars())
+                .map(|vars| vars.iter().fold(0, |mask, v| mask | 1 << position[v]))
+                .collect()
+        })
+        .collect();
+    let d = masks
+        .iter()
+        .flatten()
+        .map(|m| m.count_ones() as usize)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    let mut binom = vec![vec![0; d + 1]; nf + 1];
+    for n in 0..=nf {
+        binom[n][0] = 1;
+        for k in 1..=d.min(n) {
+            binom[n][k] = binom[n - 1][k - 1] + if k < n { binom[n - 1][k] } else { 0 };
+        }
+    }
+    // table[t] holds d[S] for |S| = t, first seen at step S itself, so it
+    // starts as the derivative at the point before it
+    let mut table: Vec<Vec<u64>> = (0..=d).map(|t| vec![0; binom[nf][t]]).collect();
+    let mut value = 0;
+    for (j, monomials) in masks.iter().enumerate() {
+        for &m in monomials {
+            if m == 0 {
+                value ^= 1 << j;
+            }
+            let mut s = m;
+            while s != 0 {
+                let t = s.count_ones() as usize;
+                if m & !s & !gray(s - 1) == 0 {
+                    table[t][subset_index(s, &binom)] ^= 1 << j;
+                }
+                s = (s - 1) & m;
+            }
+        }
+    }
+
+    let candidate = |point: u64| {
+        if rest.is_empty() {
+            return true;
+        }
+        let mut a = VarSet::empty(polys[0].ring().gens());
+        for (f, &v) in free.iter().enumerate() {
+            if point >> f & 1 == 1 {
+                a.insert(v);
+            }
+        }
+        rest.iter().all(|p| !p.eval(&a))
+    };
+    let mut solutions = Vec::new();
+    if value == 0 && candidate(0) {
+        solutions.push(0);
+    }
+    let mut index = vec![0; d + 1];
+    for i in 1..1u64 << nf {
+        let mut bits = i;
+        let mut depth = 0;
+        while bits != 0 && depth < d {
+            let k = bits.trailing_zeros() as usize;
+            index[depth + 1] = index[depth] + binom[k][depth + 1];
+            bits &= bits - 1;
+            depth += 1;
+        }
+        for t in (1..depth).rev() {
+            table[t][index[t]] ^= table[t + 1][index[t + 1]];
+        }
+        value ^= table[1][index[1]];
+        if value == 0 && candidate(gray(i)) {
+            solutions.push(gray(i));
+        }
+    }
+    solutions
+}
+
+/// All common zeros of `polys` over `ring` by enumerating every assignment,
+/// sorted by their words.
+pub fn exhaustive_search<T: MonomialOrdering>(
+    ring: &RingRef<T>,
+    polys: &[Polynomial<T>],
+    options: &ExhaustiveOptions,
+) -> Vec<VarSet> {
+    let prefix = options.prefix_vars();
+    assert!(prefix < 64, "too many prefix variables");
+    let n = ring.gens();
+    let prefix = prefix.min(n);
+    let free: Vec<usize> = (prefix..n).collect();
+    let next_job = AtomicUsize::new(0);
+    let worker = || {
+        let mut found = Vec::new();
+        loop {
+            let job = next_job.fetch_add(1, Ordering::Relaxed);
+            if job >= 1 << prefix {
+                return found;
+            }
+            let assignment: Vec<_> = (0..prefix).map(|v| (v, job >> v & 1 == 1)).collect();
+            let Ok(fixed) = fix_system(polys, &assignment) else {
+                continue;
+            };
+            let fixed = if fixed.is_empty() {
+                vec![Polynomial::zero(ring)]
+            } else {
+                fixed
+            };
+            for point in gray_search(&fixed, &free) {
+                let mut a = VarSet::empty(n);
+                for &(v, value) in &assignment {
+                    if value {
+                        a.insert(v);
+                    }
+                }
+                for (f, &v) in free.iter().enumerate() {
+                    if point >> f & 1 == 1 {
+                        a.insert(v);
+                    }
+                }
+                found.push(a);
+            }
+        }
+    };
+    let mut solutions: Vec<VarSet> = thread::scope(|s| {
+        let handles: Vec<_> = (0..options.threads.max(1))
+            .map(|_| s.spawn(worker))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect()
+    });
+    solutions.sort_by(|a, b| a.words().cmp(b.words()));
+    solutions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{exhaustive_search, ExhaustiveOptions};
+    use crate::{
+        order::DegLex,
+        poly::Polynomial,
+        ring::Ring,
+        system::is_solution,
+        testing::{assignments, random_system},
+        var::VarSet,
+    };
+
+    fn brute_force(f: &[Polynomial<DegLex>], n: usize) -> Vec<VarSet> {
+        let mut solutions: Vec<_> = assignments(n).filter(|a| is_solution(f, a)).collect();
+        solutions.sort_by(|a, b| a.words().cmp(b.words()));
+        solutions
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        let n = 10;
+        let ring = Ring::<DegLex>::new(n).into_ref();
+        for (degree, seed) in [(2, 3), (3, 5), (4, 11)] {
+            let f = random_system(&ring, 4, 3 * n, degree, seed);
+            let expected = brute_force(&f, n);
+            assert!(!expected.is_empty());
+            assert_eq!(
+                expected,
+                exhaustive_search(&ring, &f, &ExhaustiveOptions::default())
+            );
+        }
+    }
+
+    #[test]
+    fn more_than_64_polynomials() {
+        let n = 12;
+        let ring = Ring::<DegLex>::new(n).into_ref();
+        let mut f = random_system(&ring, 70, 3 * n, 2, 17);
+        // plant a solution
+        let mut planted = VarSet::empty(n);
+        for v in [1, 4, 5, 9, 11] {
+            planted.insert(v);
+        }
+        for p in &mut f {
+            if p.eval(&planted) {
+                *p += 1;
+            }
+        }
+        let solutions = exhaustive_search(&ring, &f, &ExhaustiveOptions::default());
+        assert!(solutions.contains(&planted));
+        assert_eq!(brute_force(&f, n), solutions);
+    }
+
+    #[test]
+    fn threads_split_prefix() {
+        let n = 11;
+        let ring = Ring::<DegLex>::new(n).into_ref();
+        let f = random_system(&ring, 5, 3 * n, 3, 23);
+        let options = ExhaustiveOptions {
+            prefix_vars: Some(3),
+            threads: 4,
+        };
+        let expected = brute_force(&f, n);
+        assert!(!expected.is_empty());
+        assert_eq!(expected, exhaustive_search(&ring, &f, &options));
+        let options = ExhaustiveOptions {
+            threads: 4,
+            ..Default::default()
+        };
+        assert_eq!(4, options.prefix_vars());
+        assert_eq!(expected, exhaustive_search(&ring, &f, &options));
+    }
+
+    #[test]
+    fn empty_system() {
+        let n = 5;
+        let ring = Ring::<DegLex>::new(n).into_ref();
+        let options = ExhaustiveOptions {
+            prefix_vars: Some(2),
+            threads: 2,
+        };
+        let all: Vec<VarSet> = brute_force(&[], n);
+        assert_eq!(1 << n, all.len());
+        assert_eq!(all, exhaustive_search(&ring, &[], &options));
+    }
+}
@@ -0,0 +1,286 @@
+use crate::{
+    groebner::{interreduce, Basis, PairKind},
+    linalg::{Gf2Matrix, SparseGf2Matrix},
+    mon::Monomial,
+    order::MonomialOrdering,
+    poly::Polynomial,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinearAlgebra {
+    /// Bit-packed rows, best for the small, fairly dense matrices of low degrees.
+    Dense,
+    /// Rows as column lists, best for the large and very sparse matrices of
+    /// systems with many variables.
+    Sparse,
+}
+
+#[derive(Clone, Debug)]
+pub struct F4Options {
+    pub linear_algebra: LinearAlgebra,
+}
+
+impl Default for F4Options {
+    fn default() -> Self {
+        F4Options {
+            linear_algebra: LinearAlgebra::Dense,
+        }
+    }
+}
+
+/// What happened to the critical pairs of one degree.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DegreeStats {
+    pub degree: usize,
+    pub matrices: usize,
+    pub pairs: usize,
+    /// Total over all matrices of this degree.
+    pub rows: usize,
+    pub columns: usize,
+    pub max_rows: usize,
+    pub max_columns: usize,
+    pub new_polys: usize,
+    /// Rows that vanished during elimination.
+    pub zero_reductions: usize,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct F4Stats {
+    /// Sorted by degree.
+    pub degrees: Vec<DegreeStats>,
+}
+
+impl F4Stats {
+    fn degree_mut(&mut self, degree: usize) -> &mut DegreeStats {
+        let idx = match self.degrees.binary_search_by_key(&degree, |d| d.degree) {
+            Ok(idx) => idx,
+            Err(idx) => {
+                let stats = DegreeStats {
+                    degree,
+                    ..Default::default()
+                };
+                self.degrees.insert(idx, stats);
+                idx
+            }
+        };
+        &mut self.degrees[idx]
+    }
+
+    pub fn matrices(&self) -> usize {
+        self.degrees.iter().map(|d| d.matrices).sum()
+    }
+
+    pub fn zero_reductions(&self) -> usize {
+        self.degrees.iter().map(|d| d.zero_reductions).sum()
+    }
+}
+
+/// Runs the elimination on rows given as column lists and returns the rows
+/// of the reduced echelon form with their pivots.
+pub(crate) fn eliminate(
+    rows: Vec<Vec<usize>>,
+    columns: usize,
+    linear_algebra: LinearAlgebra,
+) -> Vec<(usize, Vec<usize>)> {
+    match linear_algebra {
+        LinearAlgebra::Dense => {
+            let mut m = Gf2Matrix::new(columns);
+            for row in rows {
+                m.push_row(row);
+            }
+            let pivots = m.echelonize();
+            pivots
+                .into_iter()
+                .enumerate()
+                .map(|(r, p)| (p, m.row(r).collect()))
+                .collect()
+        }
+        LinearAlgebra::Sparse => {
+            let mut m = SparseGf2Matrix::new(columns);
+            for row in rows {
+                m.push_row(row);
+            }
+            let pivots = m.echelonize();
+            pivots
+                .into_iter()
+                .enumerate()
+                .map(|(r, p)| (p, m.row(r).collect()))
+                .collect()
+        }
+    }
+}
+
+/// One F4 step: the Macaulay matrix of `multiples` after symbolic
+/// preprocessing, reduced to echelon form. `tops` are the lcms of the
+/// critical pairs, each of which already leads two of the multiples. Returns
+/// the rows whose leading monomial is not divisible by a basis element.
+fn reduction_step<T: MonomialOrdering>(
+    basis: &Basis<T>,
+    multiples: BTreeSet<(usize, Monomial<T>)>,
+    tops: &BTreeSet<Monomial<T>>,
+    options: &F4Options,
+    stats: &mut DegreeStats,
+) -> Vec<Polynomial<T>> {
+    let mut rows: Vec<Polynomial<T>> = multiples
+        .iter()
+        .map(|(i, t)| &basis.polys[*i] * t)
+        .filter(|p| !p.is_zero())
+        .collect();
+
+    // symbolic preprocessing: add a reducer for every monomial divisible by a
+    // leading monomial of the basis. Multiples for field equations have no
+    // known leading monomial, so only the pair lcms can be skipped.
+    let active = basis.active_basis();
+    let mut columns: BTreeSet<Monomial<T>> = BTreeSet::new();
+    let mut todo = Vec::new();
+    for p in &rows {
+        for m in p.terms() {
+            if columns.insert(m.clone()) && !tops.contains(m) {
+                todo.push(m.clone());
+            }
+        }
+    }
+    while let Some(m) = todo.pop() {
+        if let Some(g) = active.iter().find(|g| g.lm().divides(&m)) {
+            let r = *g * (&m / g.lm());
+            for t in r.terms() {
+                if columns.insert(t.clone()) {
+                    todo.push(t.clone());
+                }
+            }
+            rows.push(r);
+        }
+    }
+
+    let index: BTreeMap<&Monomial<T>, usize> = columns
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, m)| (m, i))
+        .collect();
+    let matrix: Vec<Vec<usize>> = rows
+        .iter()
+        .map(|p| p.terms().map(|m| index[m]).collect())
+        .collect();
+    let n_rows = matrix.len();
+    stats.matrices += 1;
+    stats.rows += n_rows;
+    stats.columns += columns.len();
+    stats.max_rows = stats.max_rows.max(n_rows);
+    stats.max_columns = stats.max_columns.max(columns.len());
+
+    let echelon = eliminate(matrix, columns.len(), options.linear_algebra);
+    stats.zero_reductions += n_rows - echelon.len();
+
+    let by_index: Vec<&Monomial<T>> = columns.iter().rev().collect();
+    let ring = basis.polys[0].ring();
+    echelon
+        .into_iter()
+        .filter(|(pivot, _)| !active.iter().any(|g| g.lm().divides(by_index[*pivot])))
+        .map(|(_, cols)| {
+            let mut p = Polynomial::zero(ring);
+            for c in cols {
+                p += by_index[c];
+            }
+            p
+        })
+        .collect()
+}
+
+/// Reduced Gröbner basis like `groebner_basis`, computed with Faugère's F4:
+/// all critical pairs of the lowest degree are reduced at once as one
+/// Macaulay matrix over GF(2).
+pub fn f4<T: MonomialOrdering>(generators: &[Polynomial<T>]) -> Vec<Polynomial<T>> {
+    f4_with_stats(generators, &F4Options::default()).0
+}
+
+pub fn f4_with_stats<T: MonomialOrdering>(
+    generators: &[Polynomial<T>],
+    options: &F4Options,
+) -> (Vec<Polynomial<T>>, F4Stats) {
+    let mut stats = F4Stats::default();
+    let mut basis = Basis::new();
+    for f in generators {
+        let h = f.normal_form_refs(&basis.active_basis());
+        if !h.is_zero() {
+            if h.lm().is_one() {
+                return (vec![h], stats);
+            }
+            basis.update(h);
+        }
+    }
+    loop {
+        let pairs = basis.pop_lowest_degree();
+        if pairs.is_empty() {
+            break;
+        }
+        let degree = pairs[0].lcm.degree();
+        let multiples = pairs
+            .iter()
+            .flat_map(|pair| basis.pair_multiples(pair))
+            .collect();
+        let tops = pairs
+            .iter()
+            .filter(|pair| matches!(pair.kind, PairKind::Critical(..)))
+            .map(|pair| pair.lcm.clone())
+            .collect();
+        let degree_stats = stats.degree_mut(degree);
+        degree_stats.pairs += pairs.len();
+        let mut new_polys = reduction_step(&basis, multiples, &tops, options, degree_stats);
+        degree_stats.new_polys += new_polys.len();
+        new_polys.sort_by(|a, b| a.lm().cmp(b.lm()));
+        for h in new_polys {
+            // distinct pivots do not rule out divisibility among the new rows
+            let h = h.normal_form_refs(&basis.active_basis());
+            if h.is_zero() {
+                continue;
+            }
+            if h.lm().is_one() {
+                return (vec![h], stats);
+            }
+            basis.update(h);
+        }
+    }
+    let result = basis.active_basis().into_iter().cloned().collect();
+    (interreduce(result), stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{f4, f4_with_stats, F4Options, LinearAlgebra};
+    use crate::{
+        groebner::groebner_basis,
+        order::{DegLex, Lex},
+        ring::Ring,
+        testing::variables,
+    };
+
+    #[test]
+    fn agrees_with_buchberger() {
+        let ring = Ring::<DegLex>::new(6).into_ref();
+        let x = variables(&ring);
+        let f = [
+            &x[0] * &x[1] + &x[2] * &x[3] + &x[4] + 1,
+            &x[1] * &x[2] + &x[0] * &x[5] + &x[3],
+            &x[3] * &x[4] + &x[1] * &x[3] + &x[2] + &x[5],
+            &x[0] * &x[4] + &x[2] * &x[5] + &x[1],
+        ];
+        let expected: Vec<_> = groebner_basis(&f).iter().map(|g| g.to_string()).collect();
+        for linear_algebra in [LinearAlgebra::Dense, LinearAlgebra::Sparse] {
+            let (gb, stats) = f4_with_stats(&f, &F4Options { linear_algebra });
+            let gb: Vec<_> = gb.iter().map(|g| g.to_string()).collect();
+            assert_eq!(expected, gb);
+            assert!(stats.matrices() > 0);
+            assert!(stats.degrees.windows(2).all(|w| w[0].degree < w[1].degree));
+        }
+    }
+
+    #[test]
+    fn inconsistent() {
+        let ring = Ring::<Lex>::new(3).into_ref();
+        let x = variables(&ring);
+        let f = [&x[0] * &x[1] + &x[2], &x[0] + 1, &x[1] + 1, x[2].clone()];
+        assert_eq!("1", f4(&f)[0].to_string());
+    }
+}
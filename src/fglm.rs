@@ -0,0 +1,166 @@
+use crate::{mon::Monomial, order::MonomialOrdering, poly::Polynomial, ring::RingRef, var::VarSet};
+use std::collections::{BTreeSet, HashMap};
+
+fn flip_bit(words: &mut Vec<u64>, i: usize) {
+    if words.len() <= i / 64 {
+        words.resize(i / 64 + 1, 0);
+    }
+    words[i / 64] ^= 1 << (i % 64);
+}
+
+fn has_bit(words: &[u64], i: usize) -> bool {
+    words.get(i / 64).is_some_and(|w| w >> (i % 64) & 1 == 1)
+}
+
+fn xor_into(dst: &mut Vec<u64>, src: &[u64]) {
+    if dst.len() < src.len() {
+        dst.resize(src.len(), 0);
+    }
+    for (d, s) in dst.iter_mut().zip(src) {
+        *d ^= s;
+    }
+}
+
+fn bits(words: &[u64]) -> impl Iterator<Item = usize> + '_ {
+    (0..words.len() * 64).filter(move |&i| has_bit(words, i))
+}
+
+/// Normal forms as vectors over the monomials met so far, in echelon form.
+/// Each row remembers which staircase monomials it is the sum of.
+#[derive(Default)]
+struct Echelon {
+    columns: HashMap<VarSet, usize>,
+    /// `(pivot, vector, combination)`; the pivot of a row is cleared in all
+    /// later rows, so reducing in insertion order is complete.
+    rows: Vec<(usize, Vec<u64>, Vec<u64>)>,
+}
+
+impl Echelon {
+    /// Staircase monomials whose normal forms sum to `nf`, or `None` after
+    /// adding `nf` as the one of staircase monomial `index` if there are
+    /// none.
+    fn reduce<T: MonomialOrdering>(
+        &mut self,
+        nf: &Polynomial<T>,
+        index: usize,
+    ) -> Option<Vec<u64>> {
+        let mut v = Vec::new();
+        for vars in nf.terms().filter_map(|m| m.vars()) {
+            let n = self.columns.len();
+            let c = *self.columns.entry(vars.clone()).or_insert(n);
+            flip_bit(&mut v, c);
+        }
+        let mut combination = Vec::new();
+        for (pivot, row, row_combination) in &self.rows {
+            if has_bit(&v, *pivot) {
+                xor_into(&mut v, row);
+                xor_into(&mut combination, row_combination);
+            }
+        }
+        let leading = bits(&v).next();
+        match leading {
+            None => Some(combination),
+            Some(pivot) => {
+                flip_bit(&mut combination, index);
+                self.rows.push((pivot, v, combination));
+                None
+            }
+        }
+    }
+}
+
+/// Converts the reduced Gröbner basis `basis` into the reduced Gröbner basis
+/// of the same ideal over `ring`, which has the same variables under another
+/// ordering, typically `Lex` or an elimination ordering.
+///
+/// Boolean ideals are zero-dimensional, so the standard monomials of the
+/// target ordering are found by walking up from `1` in increasing order and
+/// testing the normal forms under `basis` for linear dependence. The result
+/// is in decreasing order of leading monomials, like `groebner_basis`.
+pub fn fglm<T: MonomialOrdering, U: MonomialOrdering>(
+    basis: &[Polynomial<T>],
+    ring: &RingRef<U>,
+) -> Vec<Polynomial<U>> {
+    let Some(source) = basis.first().map(|g| g.ring()) else {
+        return Vec::new();
+    };
+    assert_eq!(source.gens(), ring.gens());
+    let mut staircase: Vec<Monomial<U>> = Vec::new();
+    let mut result: Vec<Polynomial<U>> = Vec::new();
+    let mut echelon = Echelon::default();
+    let mut candidates = BTreeSet::new();
+    candidates.insert(Monomial::one(ring));
+    while let Some(m) = candidates.pop_first() {
+        if result.iter().any(|g| g.lm().divides(&m)) {
+            continue;
+        }
+        let vars = m.vars().unwrap().clone();
+        let nf = Polynomial::from_monomial(
+            source,
+            Monomial::NonZero {
+                ring: source.clone(),
+                vars: vars.clone(),
+            },
+        )
+        .normal_form(basis);
+        match echelon.reduce(&nf, staircase.len()) {
+            Some(combination) => {
+                // every staircase monomial is smaller than m
+                let mut g = Polynomial::from_monomial(ring, m);
+                for i in bits(&combination) {
+                    g += Polynomial::from_monomial(ring, staircase[i].clone());
+                }
+                result.push(g);
+            }
+            None => {
+                for v in (0..ring.gens()).filter(|&v| !vars.contains(v)) {
+                    candidates.insert(m.clone() * ring.var(v));
+                }
+                staircase.push(m);
+            }
+        }
+    }
+    result.sort_by(|a, b| b.lm().cmp(a.lm()));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fglm;
+    use crate::{
+        groebner::groebner_basis,
+        order::{DegLex, Lex, MonomialOrdering},
+        poly::Polynomial,
+        ring::Ring,
+        testing::{random_system, variables},
+    };
+
+    fn strings<O: MonomialOrdering>(basis: &[Polynomial<O>]) -> Vec<String> {
+        basis.iter().map(|g| g.to_string()).collect()
+    }
+
+    #[test]
+    fn deglex_to_lex() {
+        for (count, seed) in [(3, 7), (4, 13), (2, 29), (4, 31)] {
+            let ring = Ring::<DegLex>::new(6).into_ref();
+            let lex = ring.reordered(Lex).into_ref();
+            let f = random_system(&ring, count, 12, 2, seed);
+            let g: Vec<_> = f.iter().map(|p| p.convert(&lex)).collect();
+            let expected = groebner_basis(&g);
+            assert_eq!(
+                strings(&expected),
+                strings(&fglm(&groebner_basis(&f), &lex))
+            );
+        }
+    }
+
+    #[test]
+    fn inconsistent_and_empty() {
+        let ring = Ring::<DegLex>::new(2).into_ref();
+        let x = variables(&ring);
+        let basis = groebner_basis(&[&x[0] * &x[1] + 1, x[0].clone() + &x[1] + 1]);
+        let lex = ring.reordered(Lex).into_ref();
+        assert_eq!(vec!["1"], strings(&fglm(&basis, &lex)));
+        assert!(fglm::<DegLex, _>(&[], &lex).is_empty());
+    }
+}
@@ -0,0 +1,259 @@
+use crate::{mon::Monomial, order::MonomialOrdering, poly::Polynomial};
+
+pub(crate) enum PairKind {
+    /// S-polynomial of two basis elements.
+    Critical(usize, usize),
+    /// S-polynomial of a basis element with the field equation `x^2 + x`
+    /// of a variable of its leading monomial, which is just `x * f`, or
+    /// `(x + 1) * f` after one reduction step.
+    Field(usize, usize),
+}
+
+pub(crate) struct CriticalPair<T: MonomialOrdering> {
+    pub(crate) kind: PairKind,
+    pub(crate) lcm: Monomial<T>,
+}
+
+fn coprime<T: MonomialOrdering>(a: &Monomial<T>, b: &Monomial<T>) -> bool {
+    match (a.vars(), b.vars()) {
+        (Some(vars_a), Some(vars_b)) => vars_a.is_disjoint(vars_b),
+        _ => false,
+    }
+}
+
+/// Basis under construction together with its pending critical pairs.
+pub(crate) struct Basis<T: MonomialOrdering> {
+    pub(crate) polys: Vec<Polynomial<T>>,
+    /// Indices into `polys` whose leading monomial is not divisible by a newer one.
+    active: Vec<usize>,
+    pairs: Vec<CriticalPair<T>>,
+}
+
+impl<T: MonomialOrdering> Basis<T> {
+    pub(crate) fn new() -> Self {
+        Basis {
+            polys: Vec::new(),
+            active: Vec::new(),
+            pairs: Vec::new(),
+        }
+    }
+
+    /// Gebauer–Möller installation of a new, nonzero basis element.
+    pub(crate) fn update(&mut self, h: Polynomial<T>) {
+        let h_idx = self.polys.len();
+        let lm_h = h.lm().clone();
+
+        let new_pairs: Vec<(usize, Monomial<T>)> = self
+            .active
+            .iter()
+            .map(|&g| (g, self.polys[g].lm() * &lm_h))
+            .collect();
+        // chain criterion among the new pairs: (g, h) is dropped if the lcm of
+        // a pair still to be looked at or already kept divides its lcm; pairs
+        // with coprime leading monomials stay so they can shadow others
+        let mut kept: Vec<(usize, Monomial<T>)> = Vec::new();
+        for (i, (g, lcm)) in new_pairs.iter().enumerate() {
+            let redundant = !coprime(self.polys[*g].lm(), &lm_h)
+                && new_pairs[i + 1..]
+                    .iter()
+                    .chain(kept.iter())
+                    .any(|(_, other)| other.divides(lcm));
+            if !redundant {
+                kept.push((*g, lcm.clone()));
+            }
+        }
+
+        // old pairs whose lcm is divisible by lm(h) are redundant unless one of
+        // the pairs with h has exactly the same lcm
+        let polys = &self.polys;
+        self.pairs.retain(|pair| match pair.kind {
+            PairKind::Critical(a, b) => {
+                !(lm_h.divides(&pair.lcm)
+                    && polys[a].lm() * &lm_h != pair.lcm
+                    && polys[b].lm() * &lm_h != pair.lcm)
+            }
+            PairKind::Field(..) => true,
+        });
+
+        // product criterion
+        for (g, lcm) in kept {
+            if !coprime(self.polys[g].lm(), &lm_h) {
+                self.pairs.push(CriticalPair {
+                    kind: PairKind::Critical(g, h_idx),
+                    lcm,
+                });
+            }
+        }
+
+        // x * h == h whenever x occurs in every term of h, then the pair with
+        // the field equation of x is trivial
+        if let Some(vars) = lm_h.vars() {
+            for x in vars.iter() {
+                if !h.terms().all(|m| m.contains(x)) {
+                    self.pairs.push(CriticalPair {
+                        kind: PairKind::Field(h_idx, x),
+                        lcm: lm_h.clone(),
+                    });
+                }
+            }
+        }
+
+        let polys = &self.polys;
+        self.active.retain(|&g| !lm_h.divides(polys[g].lm()));
+        self.active.push(h_idx);
+        self.polys.push(h);
+    }
+
+    /// The multiples `t * polys[i]` whose sum is the S-polynomial of `pair`,
+    /// up to adding a basis element.
+    pub(crate) fn pair_multiples(&self, pair: &CriticalPair<T>) -> Vec<(usize, Monomial<T>)> {
+        match pair.kind {
+            PairKind::Critical(a, b) => vec![
+                (a, &pair.lcm / self.polys[a].lm()),
+                (b, &pair.lcm / self.polys[b].lm()),
+            ],
+            PairKind::Field(a, x) => {
+                let ring = self.polys[a].ring();
+                vec![
+                    (a, Monomial::from_variable(ring, ring.var(x))),
+                    (a, Monomial::one(ring)),
+                ]
+            }
+        }
+    }
+
+    fn spoly(&self, pair: &CriticalPair<T>) -> Polynomial<T> {
+        let mut s = Polynomial::zero(pair.lcm.ring().unwrap());
+        for (i, t) in self.pair_multiples(pair) {
+            s += &self.polys[i] * &t;
+        }
+        s
+    }
+
+    fn pop_pair(&mut self) -> Option<CriticalPair<T>> {
+        let (idx, _) = self
+            .pairs
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| a.lcm.cmp(&b.lcm))?;
+        Some(self.pairs.swap_remove(idx))
+    }
+
+    /// Removes all pairs whose lcm has the smallest degree.
+    pub(crate) fn pop_lowest_degree(&mut self) -> Vec<CriticalPair<T>> {
+        let Some(d) = self.pairs.iter().map(|p| p.lcm.degree()).min() else {
+            return Vec::new();
+        };
+        let (selected, rest) = std::mem::take(&mut self.pairs)
+            .into_iter()
+            .partition(|p| p.lcm.degree() == d);
+        self.pairs = rest;
+        selected
+    }
+
+    pub(crate) fn active_basis(&self) -> Vec<&Polynomial<T>> {
+        self.active.iter().map(|&g| &self.polys[g]).collect()
+    }
+
+    fn run(mut self) -> Vec<Polynomial<T>> {
+        while let Some(pair) = self.pop_pair() {
+            let s = self.spoly(&pair);
+            let h = s.normal_form_refs(&self.active_basis());
+            if !h.is_zero() {
+                if h.lm().is_one() {
+                    return vec![h];
+                }
+                self.update(h);
+            }
+        }
+        let basis = self.active_basis().into_iter().cloned().collect();
+        interreduce(basis)
+    }
+}
+
+/// Turns a basis with pairwise non-divisible leading monomials into the
+/// reduced one by reducing every tail against the other elements.
+pub(crate) fn interreduce<T: MonomialOrdering>(
+    mut basis: Vec<Polynomial<T>>,
+) -> Vec<Polynomial<T>> {
+    basis.sort_by(|a, b| b.lm().cmp(a.lm()));
+    for i in 0..basis.len() {
+        let lm = basis[i].lm().clone();
+        let tail = basis[i].clone() + &lm;
+        let others: Vec<_> = basis
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .map(|(_, g)| g)
+            .collect();
+        basis[i] = tail.normal_form_refs(&others) + &lm;
+    }
+    basis
+}
+
+/// Reduced Gröbner basis of the ideal spanned by `generators` together with
+/// the field equations `x_i^2 + x_i`.
+///
+/// The field equations are never stored, every polynomial is kept in its
+/// idempotent form and the basis returned only contains the Boolean part, in
+/// decreasing order of leading monomials. An inconsistent system gives `[1]`.
+pub fn groebner_basis<T: MonomialOrdering>(generators: &[Polynomial<T>]) -> Vec<Polynomial<T>> {
+    let mut bb = Basis::new();
+    for f in generators {
+        let h = f.normal_form_refs(&bb.active_basis());
+        if !h.is_zero() {
+            if h.lm().is_one() {
+                return vec![h];
+            }
+            bb.update(h);
+        }
+    }
+    bb.run()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::groebner_basis;
+    use crate::{
+        order::{DegLex, Lex},
+        ring::Ring,
+        testing::variables,
+    };
+
+    #[test]
+    fn linear_system() {
+        let ring = Ring::<Lex>::new(3).into_ref();
+        let x = variables(&ring);
+        let f = [&x[0] + &x[1] + 1, &x[1] + &x[2], &x[0] * &x[2] + &x[1]];
+        let gb = groebner_basis(&f);
+        let gb: Vec<_> = gb.iter().map(|g| g.to_string()).collect();
+        // x_0 = x_1 + 1 = x_2 + 1 and x_0 * x_2 = x_1 = x_2 give x_2 = 0
+        assert_eq!(vec!["x_0 + 1", "x_1", "x_2"], gb);
+    }
+
+    #[test]
+    fn inconsistent() {
+        let ring = Ring::<DegLex>::new(2).into_ref();
+        let x = variables(&ring);
+        let f = [&x[0] * &x[1] + 1, x[0].clone()];
+        assert_eq!("1", groebner_basis(&f)[0].to_string());
+    }
+
+    #[test]
+    fn reduces_generators() {
+        let ring = Ring::<DegLex>::new(5).into_ref();
+        let x = variables(&ring);
+        let f = [
+            &x[0] * &x[1] + &x[2] * &x[3] + &x[4],
+            &x[1] * &x[2] + &x[0] + 1,
+            &x[3] * &x[4] + &x[1] * &x[3] + &x[2],
+        ];
+        let gb = groebner_basis(&f);
+        for g in &f {
+            assert!(g.normal_form(&gb).is_zero());
+        }
+        let again: Vec<_> = groebner_basis(&gb).iter().map(|g| g.to_string()).collect();
+        let gb: Vec<_> = gb.iter().map(|g| g.to_string()).collect();
+        assert_eq!(gb, again);
+    }
+}
@@ -1,9 +1,28 @@
 #![feature(map_first_last)]
+pub mod bitvec;
+pub mod cnf;
+pub mod eval;
+pub mod exhaustive;
+pub mod f4;
+pub mod fglm;
+pub mod groebner;
+pub mod linalg;
+pub mod linear_lead;
+pub mod linearization;
 pub mod mon;
 pub mod order;
+pub mod parse;
 pub mod poly;
 pub mod ring;
+pub mod sat;
+pub mod sbox;
+pub mod signature;
+pub mod system;
+#[cfg(test)]
+mod testing;
 pub mod var;
+pub mod variety;
+pub mod zdd;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,319 @@
+use std::collections::BTreeMap;
+
+/// Dense matrix over GF(2) with rows packed into 64-bit words.
+///
+/// Column `j` of a row is bit `63 - j % 64` of word `j / 64`, the same
+/// layout as `VarSet`, so the leading entry of a row is its first set bit.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Gf2Matrix {
+    cols: usize,
+    words: usize,
+    rows: Vec<Vec<u64>>,
+}
+
+impl Gf2Matrix {
+    pub fn new(cols: usize) -> Self {
+        Gf2Matrix {
+            cols,
+            words: cols.div_ceil(64),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn zero(rows: usize, cols: usize) -> Self {
+        let mut m = Gf2Matrix::new(cols);
+        m.rows = vec![vec![0; m.words]; rows];
+        m
+    }
+
+    pub fn identity(n: usize) -> Self {
+        let mut m = Gf2Matrix::zero(n, n);
+        for i in 0..n {
+            m.set(i, i, true);
+        }
+        m
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.cols
+    }
+
+    /// Appends a row with ones in the given columns.
+    pub fn push_row<I: IntoIterator<Item = usize>>(&mut self, cols: I) {
+        let mut row = vec![0; self.words];
+        for c in cols {
+            row[c / 64] ^= 1 << (63 - c % 64);
+        }
+        self.rows.push(row);
+    }
+
+    pub fn push_words(&mut self, row: Vec<u64>) {
+        assert_eq!(self.words, row.len());
+        self.rows.push(row);
+    }
+
+    pub fn get(&self, r: usize, c: usize) -> bool {
+        self.rows[r][c / 64] >> (63 - c % 64) & 1 == 1
+    }
+
+    pub fn set(&mut self, r: usize, c: usize, value: bool) {
+        let mask = 1 << (63 - c % 64);
+        if value {
+            self.rows[r][c / 64] |= mask;
+        } else {
+            self.rows[r][c / 64] &= !mask;
+        }
+    }
+
+    pub fn row_words(&self, r: usize) -> &[u64] {
+        &self.rows[r]
+    }
+
+    /// Columns with a one in row `r`, increasing.
+    pub fn row(&self, r: usize) -> impl Iterator<Item = usize> + '_ {
+        self.rows[r].iter().enumerate().flat_map(|(w, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let offset = word.leading_zeros() as usize;
+                word &= !(1 << (63 - offset));
+                Some(w * 64 + offset)
+            })
+        })
+    }
+
+    pub fn is_zero_row(&self, r: usize) -> bool {
+        self.rows[r].iter().all(|&w| w == 0)
+    }
+
+    /// First column with a one in row `r`.
+    pub fn leading_column(&self, r: usize) -> Option<usize> {
+        self.rows[r]
+            .iter()
+            .position(|&w| w != 0)
+            .map(|w| w * 64 + self.rows[r][w].leading_zeros() as usize)
+    }
+
+    /// `row[dst] += row[src]`
+    pub fn add_row(&mut self, dst: usize, src: usize) {
+        if dst == src {
+            self.rows[dst].iter_mut().for_each(|w| *w = 0);
+            return;
+        }
+        let (d, s) = if dst < src {
+            let (a, b) = self.rows.split_at_mut(src);
+            (&mut a[dst], &b[0])
+        } else {
+            let (a, b) = self.rows.split_at_mut(dst);
+            (&mut b[0], &a[src])
+        };
+        for (x, y) in d.iter_mut().zip(s.iter()) {
+            *x ^= y;
+        }
+    }
+
+    pub fn swap_rows(&mut self, a: usize, b: usize) {
+        self.rows.swap(a, b);
+    }
+
+    /// Brings the matrix into reduced row echelon form, dropping zero rows.
+    /// Returns the pivot column of each remaining row.
+    pub fn echelonize(&mut self) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        let mut r = 0;
+        for c in 0..self.cols {
+            if r == self.rows.len() {
+                break;
+            }
+            let (w, mask) = (c / 64, 1 << (63 - c % 64));
+            let Some(p) = (r..self.rows.len()).find(|&i| self.rows[i][w] & mask != 0) else {
+                continue;
+            };
+            self.rows.swap(r, p);
+            for i in 0..self.rows.len() {
+                if i != r && self.rows[i][w] & mask != 0 {
+                    self.add_row(i, r);
+                }
+            }
+            pivots.push(c);
+            r += 1;
+        }
+        self.rows.truncate(r);
+        pivots
+    }
+
+    pub fn rank(&self) -> usize {
+        self.clone().echelonize().len()
+    }
+
+    /// Basis of the right kernel `{ v : M v = 0 }`, one vector per row.
+    pub fn kernel(&self) -> Gf2Matrix {
+        let mut m = self.clone();
+        let pivots = m.echelonize();
+        let mut kernel = Gf2Matrix::new(self.cols);
+        let mut is_pivot = vec![false; self.cols];
+        for &p in &pivots {
+            is_pivot[p] = true;
+        }
+        for free in (0..self.cols).filter(|&c| !is_pivot[c]) {
+            let mut v = vec![0u64; self.words];
+            v[free / 64] |= 1 << (63 - free % 64);
+            for (r, &p) in pivots.iter().enumerate() {
+                if m.get(r, free) {
+                    v[p / 64] |= 1 << (63 - p % 64);
+                }
+            }
+            kernel.push_words(v);
+        }
+        kernel
+    }
+}
+
+/// Matrix over GF(2) with rows stored as increasing lists of columns, for the
+/// very sparse Macaulay matrices of large systems.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct SparseGf2Matrix {
+    cols: usize,
+    rows: Vec<Vec<u32>>,
+}
+
+fn add_sparse(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut res = Vec::with_capacity(a.len() + b.len());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => {
+                res.push(a[i]);
+                i += 1;
+            }
+            std::cmp::Ordering::Greater => {
+                res.push(b[j]);
+                j += 1;
+            }
+            std::cmp::Ordering::Equal => {
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    res.extend_from_slice(&a[i..]);
+    res.extend_from_slice(&b[j..]);
+    res
+}
+
+impl SparseGf2Matrix {
+    pub fn new(cols: usize) -> Self {
+        SparseGf2Matrix {
+            cols,
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn nrows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn ncols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn push_row<I: IntoIterator<Item = usize>>(&mut self, cols: I) {
+        let mut row: Vec<u32> = cols.into_iter().map(|c| c as u32).collect();
+        row.sort_unstable();
+        // a column given twice cancels
+        let mut dedup = Vec::with_capacity(row.len());
+        for c in row {
+            if dedup.last() == Some(&c) {
+                dedup.pop();
+            } else {
+                dedup.push(c);
+            }
+        }
+        self.rows.push(dedup);
+    }
+
+    pub fn row(&self, r: usize) -> impl Iterator<Item = usize> + '_ {
+        self.rows[r].iter().map(|&c| c as usize)
+    }
+
+    /// Same contract as `Gf2Matrix::echelonize`.
+    pub fn echelonize(&mut self) -> Vec<usize> {
+        // forward elimination against the pivots found so far
+        let mut pivots: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+        for mut row in std::mem::take(&mut self.rows) {
+            while let Some(&lead) = row.first() {
+                match pivots.get(&lead) {
+                    Some(p) => row = add_sparse(&row, p),
+                    None => break,
+                }
+            }
+            if let Some(&lead) = row.first() {
+                pivots.insert(lead, row);
+            }
+        }
+        // back substitution, from the last pivot upwards
+        let leads: Vec<u32> = pivots.keys().copied().collect();
+        for (i, &lead) in leads.iter().enumerate().rev() {
+            let mut row = pivots.remove(&lead).unwrap();
+            for &other in &leads[i + 1..] {
+                if row.binary_search(&other).is_ok() {
+                    row = add_sparse(&row, &pivots[&other]);
+                }
+            }
+            pivots.insert(lead, row);
+        }
+        self.rows = pivots.into_values().collect();
+        leads.into_iter().map(|c| c as usize).collect()
+    }
+
+    pub fn to_dense(&self) -> Gf2Matrix {
+        let mut m = Gf2Matrix::new(self.cols);
+        for r in 0..self.rows.len() {
+            m.push_row(self.row(r));
+        }
+        m
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Gf2Matrix, SparseGf2Matrix};
+
+    #[test]
+    fn dense_and_sparse_agree() {
+        let rows: [&[usize]; 4] = [&[0, 2, 70], &[1, 2], &[0, 1, 70], &[3, 70]];
+        let mut dense = Gf2Matrix::new(71);
+        let mut sparse = SparseGf2Matrix::new(71);
+        for r in rows {
+            dense.push_row(r.iter().copied());
+            sparse.push_row(r.iter().copied());
+        }
+        let pivots = dense.echelonize();
+        assert_eq!(vec![0, 1, 3], pivots);
+        assert_eq!(pivots, sparse.echelonize());
+        assert_eq!(dense, sparse.to_dense());
+        assert_eq!(vec![0, 2, 70], dense.row(0).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn kernel() {
+        let mut m = Gf2Matrix::new(4);
+        m.push_row([0, 1]);
+        m.push_row([1, 2, 3]);
+        let k = m.kernel();
+        assert_eq!(2, k.nrows());
+        for r in 0..k.nrows() {
+            let v: Vec<_> = k.row(r).collect();
+            for i in 0..m.nrows() {
+                let dot = m.row(i).filter(|c| v.contains(c)).count();
+                assert_eq!(0, dot % 2);
+            }
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{order::MonomialOrdering, poly::Polynomial, var::VarSet};
+
+/// The polynomials of a system whose leading monomial is a single variable,
+/// prepared for substitution: lead `x_v` of `x_v + t` is replaced by `t`.
+///
+/// Every replacement is kept free of all lead variables, so one pass of
+/// substitutions gives the normal form, PolyBoRi's `ll_red_nf`.
+pub struct LinearLeadReducer<T: MonomialOrdering> {
+    leads: VarSet,
+    replacements: Vec<Option<Polynomial<T>>>,
+}
+
+impl<T: MonomialOrdering> LinearLeadReducer<T> {
+    /// Takes the polynomials of `polys` with a linear lead and ignores the
+    /// others, as well as any later one with an already taken lead.
+    pub fn new(polys: &[Polynomial<T>]) -> Self {
+        let gens = polys.first().map_or(0, |p| p.ring().gens());
+        let mut reducer = LinearLeadReducer {
+            leads: VarSet::empty(gens),
+            replacements: vec![None; gens],
+        };
+        let mut linear: Vec<&Polynomial<T>> = polys
+            .iter()
+            .filter(|p| !p.is_zero() && p.lm().degree() == 1)
+            .collect();
+        // A tail has no monomial divisible by its lead or any larger
+        // variable, so going up from the smallest lead, substituting the
+        // leads seen so far leaves each tail free of all leads, and earlier
+        // replacements never need to be revisited.
+        linear.sort_by(|a, b| a.lm().cmp(b.lm()));
+        for p in linear {
+            let v = p.lm().vars().unwrap().iter().next().unwrap();
+            if reducer.leads.contains(v) {
+                continue;
+            }
+            let ring = p.ring();
+            let tail = reducer.reduce(&(p + &Polynomial::from_variable(ring, ring.var(v))));
+            reducer.leads.insert(v);
+            reducer.replacements[v] = Some(tail);
+        }
+        reducer
+    }
+
+    /// Variables eliminated by the reducer.
+    pub fn leads(&self) -> &VarSet {
+        &self.leads
+    }
+
+    /// What lead `x_v` is replaced by.
+    pub fn replacement(&self, v: usize) -> Option<&Polynomial<T>> {
+        self.replacements.get(v).and_then(Option::as_ref)
+    }
+
+    /// `p` with every lead variable substituted, free of all of them.
+    pub fn reduce(&self, p: &Polynomial<T>) -> Polynomial<T> {
+        let vars = p.variables();
+        let subs: Vec<(usize, &Polynomial<T>)> = vars
+            .iter()
+            .filter_map(|v| self.replacement(v).map(|r| (v, r)))
+            .collect();
+        p.compose_split(&subs)
+    }
+
+    /// Reduces every polynomial and drops those that vanish, among them the
+    /// reducers themselves.
+    pub fn reduce_system(&self, polys: &[Polynomial<T>]) -> Vec<Polynomial<T>> {
+        polys
+            .iter()
+            .map(|p| self.reduce(p))
+            .filter(|p| !p.is_zero())
+            .collect()
+    }
+}
+
+impl<T: MonomialOrdering> Polynomial<T> {
+    /// Linear lead normal form; see [`LinearLeadReducer`].
+    pub fn ll_red_nf(&self, reducer: &LinearLeadReducer<T>) -> Polynomial<T> {
+        reducer.reduce(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LinearLeadReducer;
+    use crate::{
+        order::Lex,
+        ring::Ring,
+        system::{is_solution, system_variables},
+        testing::{assignments, variables},
+    };
+
+    #[test]
+    fn round_system_to_key_equations() {
+        // state s_0..s_3 are x_0..x_3, key bits are x_4..x_6; under Lex each
+        // state bit leads the equation defining it from the later ones
+        let n = 7;
+        let ring = Ring::<Lex>::new(n).into_ref();
+        let x = variables(&ring);
+        let f = [
+            &x[3] + &x[4] * &x[5] + &x[6],
+            &x[2] + &x[3] * &x[4] + &x[5],
+            &x[1] + &x[2] * &x[3] + &x[6],
+            &x[0] + &x[1] + &x[4],
+            &x[0] * &x[5] + &x[1] + 1,
+            &x[2] * &x[6] + &x[4],
+        ];
+        let reducer = LinearLeadReducer::new(&f);
+        assert_eq!(vec![0, 1, 2, 3], reducer.leads().iter().collect::<Vec<_>>());
+        let reduced = reducer.reduce_system(&f);
+        assert_eq!(2, reduced.len());
+        let vars: Vec<_> = system_variables(&reduced).iter().collect();
+        assert!(vars.iter().all(|&v| v >= 4));
+
+        let mut expected = Vec::new();
+        let mut found = Vec::new();
+        for (bits, a) in assignments(n).enumerate() {
+            if is_solution(&f, &a) {
+                expected.push(bits >> 4);
+            }
+            if bits & 0xf == 0 && is_solution(&reduced, &a) {
+                found.push(bits >> 4);
+            }
+        }
+        assert_eq!(expected, found);
+    }
+
+    #[test]
+    fn ignores_other_polynomials() {
+        let ring = Ring::<Lex>::new(3).into_ref();
+        let x = variables(&ring);
+        let f = [&x[0] + &x[0] * &x[1], &x[1] + &x[2] + 1, &x[1] + &x[2]];
+        let reducer = LinearLeadReducer::new(&f);
+        assert_eq!(vec![1], reducer.leads().iter().collect::<Vec<_>>());
+        assert_eq!("x_2 + 1", reducer.replacement(1).unwrap().to_string());
+        assert_eq!("1", f[2].ll_red_nf(&reducer).to_string());
+        assert_eq!("x_0*x_2", f[0].ll_red_nf(&reducer).to_string());
+    }
+}
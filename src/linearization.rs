@@ -0,0 +1,226 @@
+use crate::{
+    f4::{eliminate, LinearAlgebra},
+    mon::Monomial,
+    order::MonomialOrdering,
+    poly::Polynomial,
+    ring::RingRef,
+};
+use std::collections::BTreeMap;
+
+/// Reduced row echelon form of the span of `polys`, with the monomials
+/// ordered by degree first so that the span's polynomials of low degree show
+/// up as rows of low degree. Rows come out in decreasing order of leading
+/// monomials.
+fn linearize<T: MonomialOrdering>(
+    polys: &[Polynomial<T>],
+    linear_algebra: LinearAlgebra,
+) -> Vec<Polynomial<T>> {
+    let Some(ring) = polys.first().map(|p| p.ring().clone()) else {
+        return Vec::new();
+    };
+    let mut columns: Vec<&Monomial<T>> = polys.iter().flat_map(|p| p.terms()).collect();
+    columns.sort_by(|a, b| b.degree().cmp(&a.degree()).then_with(|| b.cmp(a)));
+    columns.dedup();
+    let index: BTreeMap<&Monomial<T>, usize> =
+        columns.iter().enumerate().map(|(i, &m)| (m, i)).collect();
+    let matrix: Vec<Vec<usize>> = polys
+        .iter()
+        .map(|p| {
+            let mut row: Vec<usize> = p.terms().map(|m| index[m]).collect();
+            row.sort_unstable();
+            row
+        })
+        .collect();
+    eliminate(matrix, columns.len(), linear_algebra)
+        .into_iter()
+        .map(|(_, cols)| {
+            let mut p = Polynomial::zero(&ring);
+            for c in cols {
+                p += columns[c];
+            }
+            p
+        })
+        .collect()
+}
+
+/// All monomials of degree at most `degree`.
+fn monomials_up_to<T: MonomialOrdering>(ring: &RingRef<T>, degree: usize) -> Vec<Monomial<T>> {
+    let mut res = vec![Monomial::one(ring)];
+    let mut frontier = vec![(Monomial::one(ring), 0)];
+    for _ in 0..degree {
+        let mut next = Vec::new();
+        for (m, first) in &frontier {
+            for v in *first..ring.gens() {
+                next.push((m * ring.var(v), v + 1));
+            }
+        }
+        res.extend(next.iter().map(|(m, _)| m.clone()));
+        frontier = next;
+    }
+    res
+}
+
+pub struct XlOptions {
+    /// Highest degree of the products `m * f`.
+    pub degree: usize,
+    pub linear_algebra: LinearAlgebra,
+}
+
+impl Default for XlOptions {
+    fn default() -> Self {
+        XlOptions {
+            degree: 3,
+            linear_algebra: LinearAlgebra::Sparse,
+        }
+    }
+}
+
+/// eXtended Linearization: multiplies every polynomial by all monomials that
+/// keep the product within `options.degree`, and returns a basis of the span
+/// of the products in reduced echelon form, lowest degree last. Its linear
+/// elements, if any, are the linear equations XL finds at that degree.
+pub fn xl<T: MonomialOrdering>(polys: &[Polynomial<T>], options: &XlOptions) -> Vec<Polynomial<T>> {
+    let Some(ring) = polys.first().map(|p| p.ring().clone()) else {
+        return Vec::new();
+    };
+    let multipliers = monomials_up_to(&ring, options.degree);
+    let mut products = Vec::new();
+    for p in polys.iter().filter(|p| !p.is_zero()) {
+        // the leading monomial need not have the highest degree
+        let d = p.terms().map(|m| m.degree()).max().unwrap_or(0);
+        for m in multipliers
+            .iter()
+            .take_while(|m| m.degree() + d <= options.degree)
+        {
+            products.push(p * m);
+        }
+    }
+    linearize(&products, options.linear_algebra)
+}
+
+/// What `elim_lin` leaves of a system.
+pub struct ElimLin<T: MonomialOrdering> {
+    /// Nonlinear polynomials left after substituting out the linear ones, in
+    /// reduced echelon form.
+    pub system: Vec<Polynomial<T>>,
+    /// The linear polynomials found, in the order they were eliminated. Each
+    /// one lies in the ideal of the input; `[1]` if the system is
+    /// inconsistent.
+    pub linear: Vec<Polynomial<T>>,
+}
+
+/// ElimLin: finds the linear polynomials in the span of the system by
+/// Gaussian elimination, substitutes each one out for its leading variable,
+/// and repeats until no new linear polynomial appears.
+pub fn elim_lin<T: MonomialOrdering>(polys: &[Polynomial<T>]) -> ElimLin<T> {
+    let mut system: Vec<Polynomial<T>> = polys.to_vec();
+    let mut linear = Vec::new();
+    loop {
+        let echelon = linearize(&system, LinearAlgebra::Sparse);
+        // under `Lex` a linear leading monomial says nothing about the rest
+        let (found, rest): (Vec<_>, Vec<_>) = echelon
+            .into_iter()
+            .partition(|p| p.terms().all(|m| m.degree() <= 1));
+        if found.iter().any(|p| p.lm().is_one()) {
+            let one = Polynomial::one(polys[0].ring());
+            return ElimLin {
+                system: vec![one.clone()],
+                linear: vec![one],
+            };
+        }
+        if found.is_empty() {
+            return ElimLin {
+                system: rest,
+                linear,
+            };
+        }
+        // the rows are reduced, so no leading variable occurs in another
+        // linear row and the substitutions can be done at once
+        let ring = polys[0].ring();
+        let mut replacements = vec![None; ring.gens()];
+        for l in &found {
+            let v = l.lm().vars().unwrap().iter().next().unwrap();
+            replacements[v] = Some(l + &Polynomial::from_variable(ring, ring.var(v)));
+        }
+        system = rest
+            .iter()
+            .map(|p| p.compose(&replacements))
+            .filter(|p| !p.is_zero())
+            .collect();
+        linear.extend(found);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{elim_lin, xl, XlOptions};
+    use crate::{
+        order::{DegLex, Lex},
+        ring::Ring,
+        system::is_solution,
+        testing::{assignments, random_system, variables},
+    };
+
+    #[test]
+    fn xl_finds_linear_equations() {
+        let n = 6;
+        let ring = Ring::<DegLex>::new(n).into_ref();
+        let f = random_system(&ring, 6, 2 * n, 2, 5);
+        let span = xl(&f, &XlOptions::default());
+        let linear: Vec<_> = span
+            .iter()
+            .filter(|p| p.terms().all(|m| m.degree() <= 1))
+            .collect();
+        assert!(!linear.is_empty());
+        let solutions: Vec<_> = assignments(n).filter(|a| is_solution(&f, a)).collect();
+        assert!(!solutions.is_empty());
+        for a in &solutions {
+            assert!(span.iter().all(|p| !p.eval(a)));
+        }
+    }
+
+    #[test]
+    fn elim_lin_substitutes() {
+        let n = 4;
+        let ring = Ring::<DegLex>::new(n).into_ref();
+        let x = variables(&ring);
+        // x_0 = x_1 + 1 turns x_0 x_1 + x_2 into x_2, which turns the last
+        // polynomial into x_1 + x_3
+        let f = [
+            &x[0] + &x[1] + 1,
+            &x[0] * &x[1] + &x[2],
+            &x[2] * &x[3] + &x[1] + &x[3],
+        ];
+        let res = elim_lin(&f);
+        assert!(res.system.is_empty());
+        assert_eq!(3, res.linear.len());
+        let solutions: Vec<_> = assignments(n).filter(|a| is_solution(&f, a)).collect();
+        let learned: Vec<_> = assignments(n)
+            .filter(|a| is_solution(&res.linear, a))
+            .collect();
+        assert_eq!(solutions, learned);
+
+        let g = [&x[0] + &x[1], &x[0] * &x[1] + &x[0] + 1];
+        assert_eq!("1", elim_lin(&g).linear[0].to_string());
+    }
+
+    #[test]
+    fn lex_leading_monomials() {
+        let ring = Ring::<Lex>::new(3).into_ref();
+        let x = variables(&ring);
+        // the leading monomial x_0 is linear, the polynomial is not
+        let f = [&x[0] + &x[1] * &x[2]];
+        let res = elim_lin(&f);
+        assert!(res.linear.is_empty());
+        assert_eq!(
+            vec!["x_0 + x_1*x_2"],
+            res.system.iter().map(|p| p.to_string()).collect::<Vec<_>>()
+        );
+        let g = [&x[0] + &x[1] * &x[2], &x[1] * &x[2] + &x[2]];
+        let res = elim_lin(&g);
+        assert_eq!(
+            vec!["x_0 + x_2"],
+            res.linear.iter().map(|p| p.to_string()).collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,80 +1,48 @@
-use crate::{order::MonomialOrdering, ring::BoxedRing, var::Variable};
-use sorted_vec::SortedVec;
+use crate::{
+    order::MonomialOrdering,
+    ring::RingRef,
+    var::{VarSet, Variable},
+};
 use std::{
     cmp::Ordering,
     cmp::PartialOrd,
     fmt::{self, Debug, Display},
-    ops::{Mul, MulAssign},
+    ops::{Div, Mul, MulAssign},
     usize,
 };
 
-pub const MAX_MONOMIAL_DEGREE: usize = 10;
-#[derive(Clone, Copy, Eq)]
-pub struct VariableOrder(u32);
-impl VariableOrder {
-    pub fn order(&self) -> usize {
-        self.0 as usize
-    }
-}
-impl Into<usize> for &VariableOrder {
-    fn into(self) -> usize {
-        self.0 as usize
-    }
-}
-
-impl PartialOrd for VariableOrder {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.0.partial_cmp(&other.0)
-    }
-}
-
-impl Ord for VariableOrder {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.0.cmp(&other.0)
-    }
-}
-
-impl PartialEq for VariableOrder {
-    fn eq(&self, other: &Self) -> bool {
-        self.0.eq(&other.0)
-    }
-}
-
-pub enum Monomial<'a, T: MonomialOrdering> {
-    NonZero {
-        ring: &'a BoxedRing<T>,
-        vars: SortedVec<VariableOrder>,
-    },
+pub enum Monomial<T: MonomialOrdering> {
+    NonZero { ring: RingRef<T>, vars: VarSet },
     Zero,
 }
-impl<'a, T: MonomialOrdering> Clone for Monomial<'a, T> {
+impl<T: MonomialOrdering> Clone for Monomial<T> {
     fn clone(&self) -> Self {
         match self {
             Monomial::Zero => Monomial::Zero,
             Monomial::NonZero { ring, vars } => Monomial::NonZero {
-                ring,
+                ring: ring.clone(),
                 vars: vars.clone(),
             },
         }
     }
 }
-// impl<'a, T: MonomialOrdering> Default for Monomial< T> {
+// impl<T: MonomialOrdering> Default for Monomial< T> {
 //     fn default() -> Self {
 //         Self::one()
 //     }
 // }
-impl<'a, T: MonomialOrdering> Monomial<'a, T> {
-    pub fn one(ring: &'a BoxedRing<T>) -> Self {
+impl<T: MonomialOrdering> Monomial<T> {
+    pub fn one(ring: &RingRef<T>) -> Self {
         Monomial::NonZero {
-            vars: SortedVec::with_capacity(MAX_MONOMIAL_DEGREE),
-            ring,
+            vars: VarSet::empty(ring.gens()),
+            ring: ring.clone(),
         }
     }
-    pub fn new(ring: &'a BoxedRing<T>) -> Self {
+    pub fn new(ring: &RingRef<T>) -> Self {
         Monomial::one(ring)
     }
 
-    pub fn ring(&self) -> Option<&'a BoxedRing<T>> {
+    pub fn ring(&self) -> Option<&RingRef<T>> {
         if let Monomial::NonZero { ring, .. } = self {
             Some(ring)
         } else {
@@ -99,22 +67,52 @@ impl<'a, T: MonomialOrdering> Monomial<'a, T> {
             0
         }
     }
-    pub fn vars(&self) -> Option<&[VariableOrder]> {
+    pub fn vars(&self) -> Option<&VarSet> {
         if let Monomial::NonZero { vars, .. } = self {
-            Some(vars.as_slice())
+            Some(vars)
         } else {
             None
         }
     }
 
-    pub fn from_variable(ring: &'a BoxedRing<T>, v: &'a Variable) -> Monomial<'a, T> {
-        let mut vars = SortedVec::with_capacity(MAX_MONOMIAL_DEGREE);
-        vars.insert(VariableOrder(v.order()));
-        Monomial::NonZero { vars, ring }
+    pub fn contains(&self, v: usize) -> bool {
+        self.vars().is_some_and(|vars| vars.contains(v))
+    }
+
+    /// `self | other`; zero divides nothing but zero and everything divides zero.
+    pub fn divides(&self, other: &Monomial<T>) -> bool {
+        match (self, other) {
+            (_, Monomial::Zero) => true,
+            (Monomial::Zero, _) => false,
+            (Monomial::NonZero { vars: vars_a, .. }, Monomial::NonZero { vars: vars_b, .. }) => {
+                vars_a.is_subset(vars_b)
+            }
+        }
+    }
+
+    /// `self / d` if `d` divides `self`.
+    pub fn quotient(&self, d: &Monomial<T>) -> Option<Monomial<T>> {
+        if !d.divides(self) {
+            return None;
+        }
+        let mut q = self.clone();
+        if let (Monomial::NonZero { vars, .. }, Some(d_vars)) = (&mut q, d.vars()) {
+            vars.difference_with(d_vars);
+        }
+        Some(q)
+    }
+
+    pub fn from_variable(ring: &RingRef<T>, v: &Variable) -> Monomial<T> {
+        let mut vars = VarSet::empty(ring.gens());
+        vars.insert(v.order() as usize);
+        Monomial::NonZero {
+            vars,
+            ring: ring.clone(),
+        }
     }
 }
 
-impl<'a, T: MonomialOrdering> Display for Monomial<'a, T> {
+impl<T: MonomialOrdering> Display for Monomial<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Monomial::NonZero { vars, ring } = &self {
             if vars.is_empty() {
@@ -122,10 +120,10 @@ impl<'a, T: MonomialOrdering> Display for Monomial<'a, T> {
             } else {
                 let mut mon_str = String::new();
                 for (i, v) in vars.iter().enumerate() {
-                    mon_str += ring.var(v.into()).to_string().as_str();
-                    if i != vars.len() - 1 {
+                    if i != 0 {
                         mon_str += "*";
                     }
+                    mon_str += ring.var(v).to_string().as_str();
                 }
                 write!(f, "{}", mon_str)
             }
@@ -135,28 +133,27 @@ impl<'a, T: MonomialOrdering> Display for Monomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> Debug for Monomial<'a, T> {
+impl<T: MonomialOrdering> Debug for Monomial<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Monomial::Zero => write!(f, ""),
             Monomial::NonZero { vars, ring } => {
-                let vars = vars.clone();
-                let vars: Vec<_> = vars.to_vec().iter().map(|v| ring.var(v.into())).collect();
+                let vars: Vec<_> = vars.iter().map(|v| ring.var(v)).collect();
                 write!(f, "{:?}", vars)
             }
         }
     }
 }
 
-// impl<'a, T: MonomialOrdering> From<&'a Variable> for Monomial< T> {
-//     fn from(v: &'a Variable) -> Monomial< T> {
-//         let mut vars = SortedVec::with_capacity(MAX_MONOMIAL_DEGREE);
-//         vars.insert(VariableOrder(v.order()));
+// impl<T: MonomialOrdering> From<&Variable> for Monomial< T> {
+//     fn from(v: &Variable) -> Monomial< T> {
+//         let mut vars = VarSet::empty(ring.gens());
+//         vars.insert(v.order() as usize);
 //         Monomial::NonZero { vars, ring }
 //     }
 // }
 
-impl<'a, T: MonomialOrdering> PartialEq for Monomial<'a, T> {
+impl<T: MonomialOrdering> PartialEq for Monomial<T> {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Monomial::Zero, Monomial::Zero) => true,
@@ -168,9 +165,9 @@ impl<'a, T: MonomialOrdering> PartialEq for Monomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> Mul for Monomial<'a, T> {
-    type Output = Monomial<'a, T>;
-    fn mul(self, rhs: Self) -> Monomial<'a, T> {
+impl<T: MonomialOrdering> Mul for Monomial<T> {
+    type Output = Monomial<T>;
+    fn mul(self, rhs: Self) -> Monomial<T> {
         match (self.is_zero(), rhs.is_zero()) {
             (true, _) => Monomial::Zero,
             (_, true) => Monomial::Zero,
@@ -183,9 +180,7 @@ impl<'a, T: MonomialOrdering> Mul for Monomial<'a, T> {
                     Monomial::NonZero { vars: vars_b, .. },
                 ) = (self, rhs)
                 {
-                    for v in vars_b.into_vec() {
-                        let _ = vars_a.find_or_insert(v);
-                    }
+                    vars_a.union_with(&vars_b);
                     Monomial::NonZero { vars: vars_a, ring }
                 } else {
                     panic!("Invalid State in Mul")
@@ -195,8 +190,8 @@ impl<'a, T: MonomialOrdering> Mul for Monomial<'a, T> {
     }
 }
 
-impl<'a, 'b, T: MonomialOrdering> MulAssign<&'b Monomial<'a, T>> for Monomial<'a, T> {
-    fn mul_assign(&mut self, rhs: &'b Monomial<'a, T>) {
+impl<'b, T: MonomialOrdering> MulAssign<&'b Monomial<T>> for Monomial<T> {
+    fn mul_assign(&mut self, rhs: &'b Monomial<T>) {
         match (self.is_zero(), rhs.is_zero()) {
             (true, _) => *self = Monomial::Zero,
             (_, true) => *self = Monomial::Zero,
@@ -206,9 +201,7 @@ impl<'a, 'b, T: MonomialOrdering> MulAssign<&'b Monomial<'a, T>> for Monomial<'a
                     Monomial::NonZero { vars: vars_b, .. },
                 ) = (self, rhs)
                 {
-                    for v in vars_b.iter() {
-                        let _ = vars_a.find_or_insert(*v);
-                    }
+                    vars_a.union_with(vars_b);
                 } else {
                     panic!("Invalid State in Mul")
                 }
@@ -217,9 +210,9 @@ impl<'a, 'b, T: MonomialOrdering> MulAssign<&'b Monomial<'a, T>> for Monomial<'a
     }
 }
 
-impl<'a, 'b, T: MonomialOrdering> Mul<&'b Monomial<'a, T>> for &Monomial<'a, T> {
-    type Output = <Monomial<'a, T> as Mul<Monomial<'a, T>>>::Output;
-    fn mul(self, rhs: &'b Monomial<'a, T>) -> Monomial<'a, T> {
+impl<'b, T: MonomialOrdering> Mul<&'b Monomial<T>> for &Monomial<T> {
+    type Output = <Monomial<T> as Mul<Monomial<T>>>::Output;
+    fn mul(self, rhs: &'b Monomial<T>) -> Monomial<T> {
         match (self.is_zero(), rhs.is_zero()) {
             (true, _) => Monomial::Zero,
             (_, true) => Monomial::Zero,
@@ -230,12 +223,10 @@ impl<'a, 'b, T: MonomialOrdering> Mul<&'b Monomial<'a, T>> for &Monomial<'a, T>
                 ) = (self, rhs)
                 {
                     let mut vars_res = vars_a.clone();
-                    for v in vars_b.iter() {
-                        let _ = vars_res.find_or_insert(*v);
-                    }
+                    vars_res.union_with(vars_b);
                     Monomial::NonZero {
                         vars: vars_res,
-                        ring,
+                        ring: ring.clone(),
                     }
                 } else {
                     panic!("Invalid State in Mul")
@@ -245,16 +236,16 @@ impl<'a, 'b, T: MonomialOrdering> Mul<&'b Monomial<'a, T>> for &Monomial<'a, T>
     }
 }
 
-impl<'a, 'b, T: MonomialOrdering> Mul<&'a Variable> for Monomial<'a, T> {
-    type Output = <Monomial<'a, T> as Mul<Monomial<'a, T>>>::Output;
-    fn mul(self, rhs: &'a Variable) -> Monomial<'a, T> {
+impl<T: MonomialOrdering> Mul<&Variable> for Monomial<T> {
+    type Output = <Monomial<T> as Mul<Monomial<T>>>::Output;
+    fn mul(self, rhs: &Variable) -> Monomial<T> {
         if let Monomial::NonZero {
             vars: mut vars_a,
             ring,
         } = self
         {
             //let mut vars_res = vars_a.clone();
-            let _ = vars_a.find_or_insert(VariableOrder(rhs.order()));
+            vars_a.insert(rhs.order() as usize);
             Monomial::NonZero { vars: vars_a, ring }
         } else {
             Monomial::Zero
@@ -262,15 +253,15 @@ impl<'a, 'b, T: MonomialOrdering> Mul<&'a Variable> for Monomial<'a, T> {
     }
 }
 
-impl<'a, 'b, T: MonomialOrdering> Mul<&'a Variable> for &Monomial<'a, T> {
-    type Output = <Monomial<'a, T> as Mul<Monomial<'a, T>>>::Output;
-    fn mul(self, rhs: &'a Variable) -> Monomial<'a, T> {
+impl<T: MonomialOrdering> Mul<&Variable> for &Monomial<T> {
+    type Output = <Monomial<T> as Mul<Monomial<T>>>::Output;
+    fn mul(self, rhs: &Variable) -> Monomial<T> {
         if let Monomial::NonZero { vars: vars_a, ring } = self {
             let mut vars_res = vars_a.clone();
-            let _ = vars_res.find_or_insert(VariableOrder(rhs.order()));
+            vars_res.insert(rhs.order() as usize);
             Monomial::NonZero {
                 vars: vars_res,
-                ring,
+                ring: ring.clone(),
             }
         } else {
             Monomial::Zero
@@ -278,7 +269,16 @@ impl<'a, 'b, T: MonomialOrdering> Mul<&'a Variable> for &Monomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> PartialOrd for Monomial<'a, T> {
+/// Exact division, panics unless `rhs` divides `self`.
+impl<'b, T: MonomialOrdering> Div<&'b Monomial<T>> for &Monomial<T> {
+    type Output = Monomial<T>;
+    fn div(self, rhs: &'b Monomial<T>) -> Monomial<T> {
+        self.quotient(rhs)
+            .unwrap_or_else(|| panic!("{} does not divide {}", rhs, self))
+    }
+}
+
+impl<T: MonomialOrdering> PartialOrd for Monomial<T> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(T::cmp(self, other))
         // match (self, other) {
@@ -291,24 +291,68 @@ impl<'a, T: MonomialOrdering> PartialOrd for Monomial<'a, T> {
         // }
     }
 }
-impl<'a, T: MonomialOrdering> Ord for Monomial<'a, T> {
+impl<T: MonomialOrdering> Ord for Monomial<T> {
     fn cmp(&self, other: &Self) -> Ordering {
         T::cmp(self, other)
     }
 }
 
-// impl<'a, T: MonomialOrdering> PartialEq for Monomial< T> {
+// impl<T: MonomialOrdering> PartialEq for Monomial< T> {
 //     fn eq(&self, other: &Self) -> bool {
 //         self.order == other.order
 //     }
 // }
 
-impl<'a, T: MonomialOrdering> Eq for Monomial<'a, T> {}
+impl<T: MonomialOrdering> Eq for Monomial<T> {}
 
 #[cfg(test)]
 mod tests {
+    use super::Monomial;
+    use crate::{
+        order::{DegLex, Lex},
+        ring::Ring,
+    };
+
     #[test]
     fn display() {
         assert_eq!(2 + 2, 4);
     }
+
+    #[test]
+    fn mul_is_idempotent() {
+        let ring = Ring::<Lex>::new(70);
+        let ring = &ring.into_ref();
+        let a = Monomial::from_variable(ring, ring.var(3)) * ring.var(65);
+        let b = Monomial::from_variable(ring, ring.var(65)) * ring.var(0);
+        let ab = &a * &b;
+        assert_eq!("x_0*x_3*x_65", ab.to_string());
+        assert_eq!(ab, &ab * &a);
+        assert!(a.divides(&ab) && b.divides(&ab) && !ab.divides(&a));
+        assert!(Monomial::one(ring).divides(&a));
+    }
+
+    #[test]
+    fn quotient() {
+        let ring = Ring::<Lex>::new(4);
+        let ring = &ring.into_ref();
+        let a = Monomial::from_variable(ring, ring.var(0)) * ring.var(2);
+        let b = Monomial::from_variable(ring, ring.var(2));
+        let c = Monomial::from_variable(ring, ring.var(1));
+        assert_eq!("x_0", (&a / &b).to_string());
+        assert_eq!(Some(Monomial::one(ring)), a.quotient(&a));
+        assert_eq!(None, a.quotient(&c));
+        assert!(Monomial::Zero.quotient(&a).unwrap().is_zero());
+    }
+
+    #[test]
+    fn heap_words() {
+        let ring = Ring::<DegLex>::new(300);
+        let ring = &ring.into_ref();
+        let a = Monomial::from_variable(ring, ring.var(1)) * ring.var(299);
+        let b = Monomial::from_variable(ring, ring.var(2)) * ring.var(257);
+        let c = Monomial::from_variable(ring, ring.var(0));
+        assert_eq!(2, a.degree());
+        assert!(a > b && b > c && c > Monomial::one(ring));
+        assert_eq!(vec![1, 299], a.vars().unwrap().iter().collect::<Vec<_>>());
+    }
 }
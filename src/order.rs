@@ -1,16 +1,60 @@
-use crate::mon::Monomial;
-use std::cmp::Ordering;
-pub trait MonomialOrdering: Clone + Copy + Sync {
-    fn cmp(a: &Monomial<Self>, b: &Monomial<Self>) -> Ordering;
+use crate::{
+    mon::Monomial,
+    var::{AssociatedVariableType, VarSet, Variable},
+};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display},
+    marker::PhantomData,
+    str::FromStr,
+};
+/// An ordering is a value stored in the ring, so it may be chosen at runtime
+/// as a [`DynamicOrdering`] or depend on the ring variables; the static
+/// ones are zero-sized.
+pub trait MonomialOrdering: Clone + Default + Send + Sync + 'static {
+    /// Compares two nonzero monomials by their variable sets; `vars` are the
+    /// variables of the ring, for orderings that depend on their types.
+    fn cmp_vars(&self, vars: &[Variable], a: &VarSet, b: &VarSet) -> Ordering;
+
+    /// Called by the ring with its variables whenever they or their types
+    /// change, so that `cmp_vars` need not look at them.
+    fn prepare(&mut self, _vars: &[Variable]) {}
+
+    /// `cmp_vars` on the parts of `a` and `b` whose word `i` is masked by
+    /// `mask(i)`, as block orderings compare them. The orderings here do so
+    /// in place.
+    fn cmp_masked(
+        &self,
+        vars: &[Variable],
+        a: &VarSet,
+        b: &VarSet,
+        mask: &dyn Fn(usize) -> u64,
+    ) -> Ordering {
+        self.cmp_vars(vars, &restrict(a, mask), &restrict(b, mask))
+    }
+
+    fn cmp(a: &Monomial<Self>, b: &Monomial<Self>) -> Ordering {
+        match (a, b) {
+            (Monomial::Zero, Monomial::Zero) => Ordering::Equal,
+            (Monomial::Zero, _) => Ordering::Less,
+            (_, Monomial::Zero) => Ordering::Greater,
+            (
+                Monomial::NonZero {
+                    ring, vars: vars_a, ..
+                },
+                Monomial::NonZero { vars: vars_b, .. },
+            ) => ring.ordering().cmp_vars(ring.variables(), vars_a, vars_b),
+        }
+    }
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
 struct DegRevLex;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
 pub struct DegLex;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Default)]
 pub struct Lex;
 
 // impl<'a> Compare<Monomial<'a>> for DegRevLex {
@@ -19,27 +63,52 @@ pub struct Lex;
 //     }
 // }
 
-impl<'a> MonomialOrdering for DegRevLex {
-    fn cmp(a: &Monomial<Self>, b: &Monomial<Self>) -> Ordering {
-        match (a, b) {
-            (Monomial::Zero, Monomial::Zero) => Ordering::Equal,
-            (Monomial::Zero, _) => Ordering::Less,
-            (_, Monomial::Zero) => Ordering::Greater,
-            (Monomial::NonZero { vars: vars_a, .. }, Monomial::NonZero { vars: vars_b, .. }) => {
-                match vars_a.len().cmp(&vars_b.len()) {
-                    Ordering::Equal => {
-                        let z = vars_a.iter().rev().zip(vars_b.iter().rev());
-                        for (v_a, v_b) in z {
-                            match v_b.cmp(v_a) {
-                                Ordering::Equal => {}
-                                order => return order,
-                            }
-                        }
-                        Ordering::Equal
+/// Words of `set`, word `i` masked by `mask(i)`.
+fn masked_words<'a>(
+    set: &'a VarSet,
+    mask: impl Fn(usize) -> u64 + 'a,
+) -> impl Iterator<Item = u64> + 'a {
+    set.words()
+        .iter()
+        .enumerate()
+        .map(move |(i, w)| w & mask(i))
+}
+
+fn masked_degree(set: &VarSet, mask: impl Fn(usize) -> u64) -> u32 {
+    masked_words(set, mask).map(u64::count_ones).sum()
+}
+
+impl MonomialOrdering for DegRevLex {
+    fn cmp_vars(&self, vars: &[Variable], a: &VarSet, b: &VarSet) -> Ordering {
+        self.cmp_masked(vars, a, b, &|_| !0)
+    }
+
+    fn cmp_masked(
+        &self,
+        _: &[Variable],
+        a: &VarSet,
+        b: &VarSet,
+        mask: &dyn Fn(usize) -> u64,
+    ) -> Ordering {
+        match masked_degree(a, mask).cmp(&masked_degree(b, mask)) {
+            Ordering::Equal => {
+                // the largest variable in which they differ decides,
+                // whoever contains it is the smaller monomial
+                let z = a.words().iter().zip(b.words()).enumerate().rev();
+                for (i, (w_a, w_b)) in z {
+                    let diff = (w_a ^ w_b) & mask(i);
+                    if diff != 0 {
+                        let last = diff & diff.wrapping_neg();
+                        return if w_a & last != 0 {
+                            Ordering::Less
+                        } else {
+                            Ordering::Greater
+                        };
                     }
-                    order => order,
                 }
+                Ordering::Equal
             }
+            order => order,
         }
     }
 }
@@ -50,29 +119,25 @@ impl<'a> MonomialOrdering for DegRevLex {
 //     }
 // }
 
-impl<'a> MonomialOrdering for DegLex {
-    fn cmp(a: &Monomial<Self>, b: &Monomial<Self>) -> Ordering {
-        match (a, b) {
-            (Monomial::Zero, Monomial::Zero) => Ordering::Equal,
-            (Monomial::Zero, _) => Ordering::Less,
-            (_, Monomial::Zero) => Ordering::Greater,
-            (Monomial::NonZero { vars: vars_a, .. }, Monomial::NonZero { vars: vars_b, .. }) => {
-                match vars_a.len().cmp(&vars_b.len()) {
-                    Ordering::Equal => {
-                        let z = vars_a.iter().zip(vars_b.iter());
-                        for (v_a, v_b) in z {
-                            match v_b.cmp(v_a) {
-                                Ordering::Equal => {}
-                                order => return order,
-                            }
-                        }
-                        Ordering::Equal
-                    }
-                    order => order,
-                }
-            }
+impl MonomialOrdering for DegLex {
+    fn cmp_vars(&self, _: &[Variable], vars_a: &VarSet, vars_b: &VarSet) -> Ordering {
+        match vars_a.len().cmp(&vars_b.len()) {
+            Ordering::Equal => vars_a.words().cmp(vars_b.words()),
+            order => order,
         }
     }
+
+    fn cmp_masked(
+        &self,
+        _: &[Variable],
+        a: &VarSet,
+        b: &VarSet,
+        mask: &dyn Fn(usize) -> u64,
+    ) -> Ordering {
+        masked_degree(a, mask)
+            .cmp(&masked_degree(b, mask))
+            .then_with(|| masked_words(a, mask).cmp(masked_words(b, mask)))
+    }
 }
 
 // impl<'a> Compare<Monomial<'a>> for Lex {
@@ -81,41 +146,255 @@ impl<'a> MonomialOrdering for DegLex {
 //     }
 // }
 
-impl<'a> MonomialOrdering for Lex {
-    fn cmp(a: &Monomial<Self>, b: &Monomial<Self>) -> Ordering {
-        match (a, b) {
-            (Monomial::Zero, Monomial::Zero) => Ordering::Equal,
-            (Monomial::Zero, _) => Ordering::Less,
-            (_, Monomial::Zero) => Ordering::Greater,
-            (Monomial::NonZero { vars: vars_a, .. }, Monomial::NonZero { vars: vars_b, .. }) => {
-                match (vars_a.len(), vars_b.len()) {
-                    (0, 0) => Ordering::Equal,
-                    (0, _) => Ordering::Less,
-                    (_, 0) => Ordering::Greater,
-                    _ => {
-                        let z = vars_a.iter().zip(vars_b.iter());
-                        for (v_a, v_b) in z {
-                            match v_b.cmp(v_a) {
-                                Ordering::Equal => {}
-                                order => return order,
-                            }
-                        }
-                        vars_a.len().cmp(&vars_b.len())
-                    }
-                }
+impl MonomialOrdering for Lex {
+    fn cmp_vars(&self, _: &[Variable], vars_a: &VarSet, vars_b: &VarSet) -> Ordering {
+        vars_a.words().cmp(vars_b.words())
+    }
+
+    fn cmp_masked(
+        &self,
+        _: &[Variable],
+        a: &VarSet,
+        b: &VarSet,
+        mask: &dyn Fn(usize) -> u64,
+    ) -> Ordering {
+        masked_words(a, mask).cmp(masked_words(b, mask))
+    }
+}
+
+/// `set` with word `i` masked by `mask(i)`.
+fn restrict(set: &VarSet, mask: impl Fn(usize) -> u64) -> VarSet {
+    let mut res = set.clone();
+    for (i, w) in res.words_mut().iter_mut().enumerate() {
+        *w &= mask(i);
+    }
+    res
+}
+
+/// Word `i` of the set of the variables below `split`.
+fn prefix_mask(split: usize, i: usize) -> u64 {
+    match split.saturating_sub(64 * i) {
+        0 => 0,
+        k if k >= 64 => !0,
+        k => !0 << (64 - k),
+    }
+}
+
+/// Compares the parts of `a` and `b` in the first block, whose word `i` is
+/// `first(i)`, by `first_order` and then the remaining parts by
+/// `rest_order`, all within the words masked by `mask`.
+fn cmp_blocks<A: MonomialOrdering, B: MonomialOrdering>(
+    first_order: &A,
+    rest_order: &B,
+    vars: &[Variable],
+    a: &VarSet,
+    b: &VarSet,
+    mask: &dyn Fn(usize) -> u64,
+    first: impl Fn(usize) -> u64,
+) -> Ordering {
+    first_order
+        .cmp_masked(vars, a, b, &|i| mask(i) & first(i))
+        .then_with(|| rest_order.cmp_masked(vars, a, b, &|i| mask(i) & !first(i)))
+}
+
+/// Product ordering: the variables below `SPLIT` are compared by `A`, ties
+/// are broken by `B` on the remaining ones. Any monomial with a larger
+/// first-block part is larger, so it eliminates the first block.
+#[derive(Clone, Copy, Default)]
+pub struct Block<A, B, const SPLIT: usize> {
+    first: A,
+    rest: B,
+}
+
+impl<A, B, const SPLIT: usize> Block<A, B, SPLIT> {
+    pub fn new(first: A, rest: B) -> Self {
+        Block { first, rest }
+    }
+}
+
+impl<A: MonomialOrdering, B: MonomialOrdering, const SPLIT: usize> MonomialOrdering
+    for Block<A, B, SPLIT>
+{
+    fn cmp_vars(&self, vars: &[Variable], a: &VarSet, b: &VarSet) -> Ordering {
+        self.cmp_masked(vars, a, b, &|_| !0)
+    }
+
+    fn cmp_masked(
+        &self,
+        vars: &[Variable],
+        a: &VarSet,
+        b: &VarSet,
+        mask: &dyn Fn(usize) -> u64,
+    ) -> Ordering {
+        cmp_blocks(&self.first, &self.rest, vars, a, b, mask, |i| {
+            prefix_mask(SPLIT, i)
+        })
+    }
+
+    fn prepare(&mut self, vars: &[Variable]) {
+        self.first.prepare(vars);
+        self.rest.prepare(vars);
+    }
+}
+
+/// Variable types an [`Elimination`] ordering eliminates.
+pub trait TypePattern: Clone + Copy + Default + Send + Sync + 'static {
+    fn matches(t: AssociatedVariableType) -> bool;
+}
+
+/// The state variables `X` and `Y`.
+#[derive(Clone, Copy, Default)]
+pub struct StateVars;
+
+impl TypePattern for StateVars {
+    fn matches(t: AssociatedVariableType) -> bool {
+        matches!(
+            t,
+            AssociatedVariableType::X(..) | AssociatedVariableType::Y(..)
+        )
+    }
+}
+
+/// The key variables `K`.
+#[derive(Clone, Copy, Default)]
+pub struct KeyVars;
+
+impl TypePattern for KeyVars {
+    fn matches(t: AssociatedVariableType) -> bool {
+        matches!(t, AssociatedVariableType::K(..))
+    }
+}
+
+/// Block ordering whose first block is given by the variable types: the
+/// variables matching `P` are compared by `A`, the others by `B`. With
+/// `P = StateVars`, a Gröbner basis contains a basis of the key-only
+/// equations.
+#[derive(Clone, Default)]
+pub struct Elimination<P, A, B> {
+    pattern: PhantomData<P>,
+    first: A,
+    rest: B,
+    /// Words of the set of variables matching `P`.
+    first_block: Vec<u64>,
+}
+
+impl<P: TypePattern, A: MonomialOrdering, B: MonomialOrdering> MonomialOrdering
+    for Elimination<P, A, B>
+{
+    fn cmp_vars(&self, vars: &[Variable], a: &VarSet, b: &VarSet) -> Ordering {
+        self.cmp_masked(vars, a, b, &|_| !0)
+    }
+
+    fn cmp_masked(
+        &self,
+        vars: &[Variable],
+        a: &VarSet,
+        b: &VarSet,
+        mask: &dyn Fn(usize) -> u64,
+    ) -> Ordering {
+        cmp_blocks(&self.first, &self.rest, vars, a, b, mask, |i| {
+            self.first_block.get(i).copied().unwrap_or(0)
+        })
+    }
+
+    fn prepare(&mut self, vars: &[Variable]) {
+        let mut first_block = VarSet::empty(vars.len());
+        for (v, var) in vars.iter().enumerate() {
+            if P::matches(var.associated_type()) {
+                first_block.insert(v);
             }
         }
+        self.first_block = first_block.words().to_vec();
+        self.first.prepare(vars);
+        self.rest.prepare(vars);
+    }
+}
+
+/// Ordering chosen at runtime, e.g. from a configuration file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DynamicOrdering {
+    Lex,
+    #[default]
+    DegLex,
+    DegRevLex,
+    /// `DegLex` on the variables below `split`, then `DegLex` on the rest.
+    DegLexBlocks {
+        split: usize,
+    },
+}
+
+impl MonomialOrdering for DynamicOrdering {
+    fn cmp_vars(&self, vars: &[Variable], a: &VarSet, b: &VarSet) -> Ordering {
+        match *self {
+            DynamicOrdering::Lex => Lex.cmp_vars(vars, a, b),
+            DynamicOrdering::DegLex => DegLex.cmp_vars(vars, a, b),
+            DynamicOrdering::DegRevLex => DegRevLex.cmp_vars(vars, a, b),
+            DynamicOrdering::DegLexBlocks { .. } => self.cmp_masked(vars, a, b, &|_| !0),
+        }
+    }
+
+    fn cmp_masked(
+        &self,
+        vars: &[Variable],
+        a: &VarSet,
+        b: &VarSet,
+        mask: &dyn Fn(usize) -> u64,
+    ) -> Ordering {
+        match *self {
+            DynamicOrdering::Lex => Lex.cmp_masked(vars, a, b, mask),
+            DynamicOrdering::DegLex => DegLex.cmp_masked(vars, a, b, mask),
+            DynamicOrdering::DegRevLex => DegRevLex.cmp_masked(vars, a, b, mask),
+            DynamicOrdering::DegLexBlocks { split } => {
+                cmp_blocks(&DegLex, &DegLex, vars, a, b, mask, |i| {
+                    prefix_mask(split, i)
+                })
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownOrdering(pub String);
+
+impl Display for UnknownOrdering {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown monomial ordering `{}`", self.0)
     }
 }
 
+impl std::error::Error for UnknownOrdering {}
+
+impl FromStr for DynamicOrdering {
+    type Err = UnknownOrdering;
+
+    /// Accepts `lex`, `deglex`, `degrevlex` and `deglex:<split>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "lex" => Ok(DynamicOrdering::Lex),
+            "deglex" => Ok(DynamicOrdering::DegLex),
+            "degrevlex" => Ok(DynamicOrdering::DegRevLex),
+            other => other
+                .strip_prefix("deglex:")
+                .and_then(|split| split.parse().ok())
+                .map(|split| DynamicOrdering::DegLexBlocks { split })
+                .ok_or_else(|| UnknownOrdering(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
 mod tests {
-    use super::MonomialOrdering;
+    use super::{prefix_mask, restrict, MonomialOrdering};
     use crate::{
-        order::{DegLex, DegRevLex, Lex},
+        groebner::groebner_basis,
+        order::{Block, DegLex, DegRevLex, DynamicOrdering, Elimination, KeyVars, Lex, StateVars},
         poly::Polynomial,
-        ring::{BoxedRing, Ring},
+        ring::{Ring, RingBuilder, RingRef},
+        system::polys_within,
+        var::{AssociatedVariableType, VarSet},
     };
-    fn test_poly<O: MonomialOrdering>(ring: &BoxedRing<O>) -> Polynomial<O> {
+    use std::cmp::Ordering;
+    fn test_poly<O: MonomialOrdering>(ring: &RingRef<O>) -> Polynomial<O> {
         let x: Vec<_> = (0..4)
             .map(|i| Polynomial::from_variable(ring, ring.var(i)))
             .collect();
@@ -135,7 +414,7 @@ mod tests {
     fn lex_order() {
         let ordering = Lex;
         let ring = Ring::<Lex>::new(4);
-        let ring = &Box::new(ring);
+        let ring = &ring.into_ref();
         let p = test_poly(ring);
         assert_eq!(
             "x_0*x_1*x_2 + x_0*x_2 + x_0 + x_1*x_2*x_3 + x_1*x_3 + x_2 + x_3",
@@ -146,7 +425,7 @@ mod tests {
     fn degrevlex_order() {
         let ordering = DegRevLex;
         let ring = Ring::<DegRevLex>::new(4);
-        let ring = &Box::new(ring);
+        let ring = &ring.into_ref();
         let p = test_poly(ring);
         assert_eq!(
             "x_2*x_1*x_0 + x_3*x_2*x_1 + x_2*x_0 + x_3*x_1 + x_0 + x_2 + x_3",
@@ -158,11 +437,150 @@ mod tests {
     fn deglex_order() {
         let ordering = DegLex;
         let ring = Ring::<DegLex>::new(4);
-        let ring = &Box::new(ring);
+        let ring = &ring.into_ref();
         let p = test_poly(ring);
         assert_eq!(
             "x_0*x_1*x_2 + x_1*x_2*x_3 + x_0*x_2 + x_1*x_3 + x_0 + x_2 + x_3",
             p.to_string()
         );
     }
+
+    #[test]
+    fn block_order() {
+        let ring = Ring::<Block<DegLex, DegLex, 1>>::new(4).into_ref();
+        assert_eq!(
+            "x_0*x_1*x_2 + x_0*x_2 + x_0 + x_1*x_2*x_3 + x_1*x_3 + x_2 + x_3",
+            test_poly(&ring).to_string()
+        );
+        let ring = Ring::<Block<Lex, DegLex, 0>>::new(4).into_ref();
+        assert_eq!(
+            "x_0*x_1*x_2 + x_1*x_2*x_3 + x_0*x_2 + x_1*x_3 + x_0 + x_2 + x_3",
+            test_poly(&ring).to_string()
+        );
+    }
+
+    #[test]
+    fn blocks_across_words() {
+        let n = 130;
+        let set = |vars: &[usize]| {
+            let mut s = VarSet::empty(n);
+            for &v in vars {
+                s.insert(v);
+            }
+            s
+        };
+        // x_69 decides if it is in the first block, the degree otherwise
+        let (a, b) = (set(&[69, 129]), set(&[70, 71, 128]));
+        let ring = Ring::<Block<DegLex, DegLex, 70>>::new(n);
+        assert_eq!(
+            Ordering::Greater,
+            ring.ordering().cmp_vars(ring.variables(), &a, &b)
+        );
+        let ring = Ring::<Block<DegLex, DegLex, 69>>::new(n);
+        assert_eq!(
+            Ordering::Less,
+            ring.ordering().cmp_vars(ring.variables(), &a, &b)
+        );
+        let ring = Ring::with_ordering(n, DynamicOrdering::DegLexBlocks { split: 70 });
+        assert_eq!(
+            Ordering::Greater,
+            ring.ordering().cmp_vars(ring.variables(), &a, &b)
+        );
+
+        let mut ring = Ring::<Elimination<KeyVars, DegLex, DegLex>>::new(n);
+        for v in 0..70 {
+            ring.set_variable_type(AssociatedVariableType::K(v), v);
+        }
+        assert_eq!(
+            Ordering::Greater,
+            ring.ordering().cmp_vars(ring.variables(), &a, &b)
+        );
+        ring.set_variable_type(AssociatedVariableType::NoType, 69);
+        assert_eq!(
+            Ordering::Less,
+            ring.ordering().cmp_vars(ring.variables(), &a, &b)
+        );
+    }
+
+    #[test]
+    fn masked_comparison_matches_restriction() {
+        fn check<O: MonomialOrdering>(ordering: O, sets: &[VarSet]) {
+            let ring = Ring::with_ordering(300, ordering);
+            let vars = ring.variables();
+            let mask = |i| prefix_mask(200, i) & !prefix_mask(30, i);
+            for a in sets {
+                for b in sets {
+                    assert_eq!(
+                        ring.ordering()
+                            .cmp_vars(vars, &restrict(a, mask), &restrict(b, mask)),
+                        ring.ordering().cmp_masked(vars, a, b, &mask)
+                    );
+                }
+            }
+        }
+        let mut state = 1u64;
+        let sets: Vec<_> = (0..20)
+            .map(|_| {
+                let mut s = VarSet::empty(300);
+                for _ in 0..6 {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    s.insert(state as usize % 300);
+                }
+                s
+            })
+            .collect();
+        check(Lex, &sets);
+        check(DegLex, &sets);
+        check(DegRevLex, &sets);
+        check(DynamicOrdering::DegLexBlocks { split: 100 }, &sets);
+        check(Block::<Lex, DegRevLex, 150>::default(), &sets);
+    }
+
+    #[test]
+    fn eliminates_state_variables() {
+        // the state comes after the key, yet is eliminated first
+        let ring = RingBuilder::new()
+            .key(2)
+            .x(1, 2)
+            .build::<Elimination<StateVars, DegLex, DegLex>>()
+            .unwrap()
+            .into_ref();
+        let var = |v| Polynomial::from_variable(&ring, v);
+        let (k0, k1) = (var(ring.key_var(0)), var(ring.key_var(1)));
+        let (x0, x1) = (var(ring.x_var(0, 0)), var(ring.x_var(0, 1)));
+        let f = [&x0 + &k0 + &k1, &x1 + &x0 * &k1 + 1, &x0 * &x1];
+        let gb = groebner_basis(&f);
+        let keys = ring.vars_matching(|t| matches!(t, AssociatedVariableType::K(_)));
+        let key_only: Vec<_> = polys_within(&gb, &keys)
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(vec!["k_0*k_1 + k_0"], key_only);
+    }
+
+    #[test]
+    fn dynamic_order() {
+        let ordering: DynamicOrdering = "deglex".parse().unwrap();
+        let ring = Ring::with_ordering(4, ordering).into_ref();
+        let p = test_poly(&ring);
+        assert_eq!(
+            "x_0*x_1*x_2 + x_1*x_2*x_3 + x_0*x_2 + x_1*x_3 + x_0 + x_2 + x_3",
+            p.to_string()
+        );
+        let lex = ring.reordered(Lex).into_ref();
+        assert_eq!(
+            "x_0*x_1*x_2 + x_0*x_2 + x_0 + x_1*x_2*x_3 + x_1*x_3 + x_2 + x_3",
+            p.convert(&lex).to_string()
+        );
+        let blocks = ring
+            .reordered("DegLex:1".parse::<DynamicOrdering>().unwrap())
+            .into_ref();
+        assert_eq!(
+            test_poly(&Ring::<Block<DegLex, DegLex, 1>>::new(4).into_ref()).to_string(),
+            p.convert(&blocks).to_string()
+        );
+        assert!("grevlex".parse::<DynamicOrdering>().is_err());
+    }
 }
@@ -0,0 +1,324 @@
+use crate::{order::MonomialOrdering, poly::Polynomial, ring::RingRef};
+use std::{
+    collections::HashMap,
+    fmt::{self, Display},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    UnknownVariable(String),
+    /// The name belongs to more than one variable of the ring.
+    DuplicateVariable(String),
+    UnexpectedToken(String),
+    UnexpectedEnd,
+    UnclosedParenthesis,
+}
+
+/// Parse failure at a 1-based line and column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: ", self.line, self.column)?;
+        match &self.kind {
+            ParseErrorKind::UnknownVariable(name) => write!(f, "unknown variable `{}`", name),
+            ParseErrorKind::DuplicateVariable(name) => {
+                write!(f, "variable name `{}` is declared more than once", name)
+            }
+            ParseErrorKind::UnexpectedToken(token) => write!(f, "unexpected `{}`", token),
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseErrorKind::UnclosedParenthesis => write!(f, "unclosed parenthesis"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Plus,
+    Star,
+    Open,
+    Close,
+    /// A variable name or an integer constant.
+    Word(String),
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Plus => write!(f, "+"),
+            Token::Star => write!(f, "*"),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+            Token::Word(w) => write!(f, "{}", w),
+        }
+    }
+}
+
+/// 1-based line and column.
+type Position = (usize, usize);
+
+/// Splits at whitespace and `+*()`, so variable names may contain anything
+/// else, e.g. `k[3]` or `x.1`.
+fn tokenize(s: &str, first_line: usize) -> (Vec<(Token, Position)>, Position) {
+    let mut tokens = Vec::new();
+    let (mut line, mut column) = (first_line, 1);
+    let mut word: Option<(String, usize, usize)> = None;
+    for c in s.chars() {
+        let token = match c {
+            '+' => Some(Token::Plus),
+            '*' => Some(Token::Star),
+            '(' => Some(Token::Open),
+            ')' => Some(Token::Close),
+            _ => None,
+        };
+        if token.is_some() || c.is_whitespace() {
+            if let Some((w, l, col)) = word.take() {
+                tokens.push((Token::Word(w), (l, col)));
+            }
+        } else {
+            word.get_or_insert_with(|| (String::new(), line, column))
+                .0
+                .push(c);
+        }
+        if let Some(token) = token {
+            tokens.push((token, (line, column)));
+        }
+        if c == '\n' {
+            line += 1;
+            column = 1;
+        } else {
+            column += 1;
+        }
+    }
+    if let Some((w, l, col)) = word {
+        tokens.push((Token::Word(w), (l, col)));
+    }
+    (tokens, (line, column))
+}
+
+/// Reads polynomials over one ring, resolving names through the variable
+/// names of the ring.
+///
+/// Sums are written with `+`, products with `*`, and parentheses may group
+/// either; integer constants are taken modulo 2.
+pub struct PolynomialParser<T: MonomialOrdering> {
+    ring: RingRef<T>,
+    /// `None` for names shared by several variables.
+    names: HashMap<String, Option<usize>>,
+}
+
+struct Cursor<'a, T: MonomialOrdering> {
+    parser: &'a PolynomialParser<T>,
+    tokens: Vec<(Token, Position)>,
+    pos: usize,
+    end: Position,
+}
+
+impl<'a, T: MonomialOrdering> Cursor<'a, T> {
+    fn error(&self, kind: ParseErrorKind) -> ParseError {
+        let (line, column) = self.tokens.get(self.pos).map_or(self.end, |(_, at)| *at);
+        ParseError { line, column, kind }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn sum(&mut self) -> Result<Polynomial<T>, ParseError> {
+        let mut p = self.product()?;
+        while self.peek() == Some(&Token::Plus) {
+            self.pos += 1;
+            p += self.product()?;
+        }
+        Ok(p)
+    }
+
+    fn product(&mut self) -> Result<Polynomial<T>, ParseError> {
+        let mut p = self.atom()?;
+        while self.peek() == Some(&Token::Star) {
+            self.pos += 1;
+            p = p * self.atom()?;
+        }
+        Ok(p)
+    }
+
+    fn atom(&mut self) -> Result<Polynomial<T>, ParseError> {
+        let ring = &self.parser.ring;
+        match self.peek().cloned() {
+            Some(Token::Open) => {
+                let open = self.pos;
+                self.pos += 1;
+                let p = self.sum()?;
+                if self.peek() != Some(&Token::Close) {
+                    if self.peek().is_none() {
+                        self.pos = open;
+                        return Err(self.error(ParseErrorKind::UnclosedParenthesis));
+                    }
+                    let token = self.peek().unwrap().to_string();
+                    return Err(self.error(ParseErrorKind::UnexpectedToken(token)));
+                }
+                self.pos += 1;
+                Ok(p)
+            }
+            Some(Token::Word(w)) => {
+                let p = if let Some(&v) = self.parser.names.get(&w) {
+                    let Some(v) = v else {
+                        return Err(self.error(ParseErrorKind::DuplicateVariable(w)));
+                    };
+                    Polynomial::from_variable(ring, ring.var(v))
+                } else if let Some(digit) = w
+                    .bytes()
+                    .last()
+                    .filter(|_| w.bytes().all(|b| b.is_ascii_digit()))
+                {
+                    if (digit - b'0') % 2 == 1 {
+                        Polynomial::one(ring)
+                    } else {
+                        Polynomial::zero(ring)
+                    }
+                } else {
+                    return Err(self.error(ParseErrorKind::UnknownVariable(w)));
+                };
+                self.pos += 1;
+                Ok(p)
+            }
+            Some(token) => Err(self.error(ParseErrorKind::UnexpectedToken(token.to_string()))),
+            None => Err(self.error(ParseErrorKind::UnexpectedEnd)),
+        }
+    }
+}
+
+impl<T: MonomialOrdering> PolynomialParser<T> {
+    pub fn new(ring: &RingRef<T>) -> Self {
+        let mut names = HashMap::new();
+        for i in 0..ring.gens() {
+            names
+                .entry(ring.var(i).name().to_string())
+                .and_modify(|v| *v = None)
+                .or_insert(Some(i));
+        }
+        PolynomialParser {
+            ring: ring.clone(),
+            names,
+        }
+    }
+
+    fn parse_at(&self, s: &str, first_line: usize) -> Result<Polynomial<T>, ParseError> {
+        let (tokens, end) = tokenize(s, first_line);
+        let mut cursor = Cursor {
+            parser: self,
+            tokens,
+            pos: 0,
+            end,
+        };
+        let p = cursor.sum()?;
+        match cursor.peek() {
+            None => Ok(p),
+            Some(token) => {
+                let token = token.to_string();
+                Err(cursor.error(ParseErrorKind::UnexpectedToken(token)))
+            }
+        }
+    }
+
+    pub fn parse(&self, s: &str) -> Result<Polynomial<T>, ParseError> {
+        self.parse_at(s, 1)
+    }
+
+    /// One polynomial per line. Empty lines and everything after a `#` are
+    /// skipped.
+    pub fn parse_system(&self, s: &str) -> Result<Vec<Polynomial<T>>, ParseError> {
+        let mut polys = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap();
+            if !line.trim().is_empty() {
+                polys.push(self.parse_at(line, i + 1)?);
+            }
+        }
+        Ok(polys)
+    }
+}
+
+impl<T: MonomialOrdering> Polynomial<T> {
+    /// Parses `s` with the variable names of `ring`, see `PolynomialParser`.
+    pub fn parse(ring: &RingRef<T>, s: &str) -> Result<Polynomial<T>, ParseError> {
+        PolynomialParser::new(ring).parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ParseError, ParseErrorKind, PolynomialParser};
+    use crate::{order::DegLex, poly::Polynomial, ring::Ring};
+
+    #[test]
+    fn parse_polynomial() {
+        let ring = Ring::<DegLex>::new(3).into_ref();
+        let p = Polynomial::parse(&ring, "x_0*x_1 + x_2 + 1").unwrap();
+        assert_eq!("x_0*x_1 + x_2 + 1", p.to_string());
+        let p = Polynomial::parse(&ring, "(x_0 + 1) * (x_1 + x_0) + 3").unwrap();
+        assert_eq!("x_0*x_1 + x_1 + 1", p.to_string());
+    }
+
+    #[test]
+    fn ring_names() {
+        let mut ring = Ring::<DegLex>::new(3);
+        ring.set_variable_name("k[0]", 0);
+        ring.set_variable_name("k[1]", 1);
+        ring.set_variable_name("y", 2);
+        let ring = ring.into_ref();
+        let p = Polynomial::parse(&ring, "k[0]*y+k[1]*(y+1)").unwrap();
+        assert_eq!("k[0]*y + k[1]*y + k[1]", p.to_string());
+    }
+
+    #[test]
+    fn duplicate_names() {
+        let mut ring = Ring::<DegLex>::new(3);
+        ring.set_variable_name("k", 0);
+        ring.set_variable_name("k", 2);
+        let ring = ring.into_ref();
+        let e = Polynomial::parse(&ring, "x_1 + k").err().unwrap();
+        assert_eq!(ParseErrorKind::DuplicateVariable("k".to_string()), e.kind);
+        assert_eq!(
+            "1:7: variable name `k` is declared more than once",
+            e.to_string()
+        );
+        assert!(Polynomial::parse(&ring, "x_1 + 1").is_ok());
+    }
+
+    #[test]
+    fn errors() {
+        let ring = Ring::<DegLex>::new(3).into_ref();
+        let parser = PolynomialParser::new(&ring);
+        let err = |s: &str| parser.parse_system(s).err().unwrap();
+        assert_eq!(
+            ParseError {
+                line: 2,
+                column: 7,
+                kind: ParseErrorKind::UnknownVariable("x_3".to_string())
+            },
+            err("x_0 + 1\nx_1 + x_3")
+        );
+        assert_eq!(ParseErrorKind::UnclosedParenthesis, err("(x_0 + x_1").kind);
+        assert_eq!(ParseErrorKind::UnexpectedEnd, err("x_0 *").kind);
+        let e = err("# comment\n\nx_0 x_1");
+        assert_eq!((3, 5), (e.line, e.column));
+        assert_eq!("3:5: unexpected `x_1`", e.to_string());
+    }
+
+    #[test]
+    fn system() {
+        let ring = Ring::<DegLex>::new(3).into_ref();
+        let text = "x_0*x_1 + x_2  # first\n\n  x_1 + 1\n";
+        let f = PolynomialParser::new(&ring).parse_system(text).unwrap();
+        let f: Vec<_> = f.iter().map(|p| p.to_string()).collect();
+        assert_eq!(vec!["x_0*x_1 + x_2", "x_1 + 1"], f);
+    }
+}
@@ -1,51 +1,63 @@
-use crate::{mon::Monomial, order::MonomialOrdering, ring::BoxedRing, var::Variable};
+use crate::{
+    mon::Monomial,
+    order::MonomialOrdering,
+    ring::RingRef,
+    var::{AssociatedVariableType, VarSet, Variable},
+};
 use std::{
-    collections::BTreeSet,
+    collections::{btree_set, BTreeSet},
     fmt::Display,
     ops::{Add, AddAssign, Mul},
 };
 
-pub struct Polynomial<'a, T: MonomialOrdering> {
-    mons: BTreeSet<Monomial<'a, T>>,
-    ring: &'a BoxedRing<T>,
+mod ideal;
+
+pub use ideal::Ideal;
+
+pub struct Polynomial<T: MonomialOrdering> {
+    mons: BTreeSet<Monomial<T>>,
+    ring: RingRef<T>,
 }
 
-impl<'a, T: MonomialOrdering> Clone for Polynomial<'a, T> {
+impl<T: MonomialOrdering> Clone for Polynomial<T> {
     fn clone(&self) -> Self {
         Polynomial {
             mons: self.mons.clone(),
-            ring: self.ring,
+            ring: self.ring.clone(),
         }
     }
 }
 
-// impl<'a, T: MonomialOrdering> Default for Polynomial< T> {
+// impl<T: MonomialOrdering> Default for Polynomial< T> {
 //     fn default() -> Self {
 //         Self::new()
 //     }
 // }
 
-impl<'a, T: MonomialOrdering> Polynomial<'a, T> {
-    pub fn new(ring: &'a BoxedRing<T>) -> Self {
+impl<T: MonomialOrdering> Polynomial<T> {
+    pub fn new(ring: &RingRef<T>) -> Self {
         Polynomial::zero(ring)
     }
-    pub fn zero(ring: &'a BoxedRing<T>) -> Self {
+    pub fn zero(ring: &RingRef<T>) -> Self {
         Polynomial {
             mons: BTreeSet::new(),
-            ring,
+            ring: ring.clone(),
         }
     }
-    pub fn one(ring: &'a BoxedRing<T>) -> Self {
+    pub fn one(ring: &RingRef<T>) -> Self {
         let mut mons = BTreeSet::new();
         mons.insert(Monomial::one(ring));
-        Polynomial { mons, ring }
+        Polynomial {
+            mons,
+            ring: ring.clone(),
+        }
     }
 
-    pub fn ring(&self) -> &'a BoxedRing<T> {
-        self.ring
+    pub fn ring(&self) -> &RingRef<T> {
+        &self.ring
     }
 
-    pub fn lm(&self) -> &Monomial<'a, T> {
+    pub fn lm(&self) -> &Monomial<T> {
         let lt = self.mons.last();
         lt.map_or(&Monomial::Zero, |m| m)
     }
@@ -81,18 +93,30 @@ impl<'a, T: MonomialOrdering> Polynomial<'a, T> {
         // }
         // self.mons = just_mons;
     }
-    pub fn from_monomial(ring: &'a BoxedRing<T>, m: Monomial<'a, T>) -> Self {
+    pub fn from_monomial(ring: &RingRef<T>, m: Monomial<T>) -> Self {
         let mut pol = Self::new(ring);
         pol.mons.insert(m);
         pol
     }
 
-    pub fn from_variable(ring: &'a BoxedRing<T>, v: &'a Variable) -> Self {
+    pub fn from_variable(ring: &RingRef<T>, v: &Variable) -> Self {
         let mut pol = Self::new(ring);
         pol.mons.insert(Monomial::from_variable(ring, v));
         pol
     }
 
+    pub fn terms(&self) -> btree_set::Iter<'_, Monomial<T>> {
+        self.mons.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.mons.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mons.is_empty()
+    }
+
     pub fn is_zero(&self) -> bool {
         //self.justify();
         self.mons.is_empty() || (self.mons.len() == 1 && self.mons.last().unwrap().is_zero())
@@ -108,7 +132,205 @@ impl<'a, T: MonomialOrdering> Polynomial<'a, T> {
     // }
 }
 
-impl<'a, T: MonomialOrdering> Display for Polynomial<'a, T> {
+/// How far `Polynomial::reduce` goes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reduction {
+    /// Every term of the result is irreducible.
+    Full,
+    /// Only the leading monomial is reduced, until it is irreducible or the
+    /// polynomial vanishes.
+    Top,
+}
+
+/// `dividend = sum(quotients[i] * basis[i]) + remainder`, as returned by
+/// `Polynomial::divide`.
+pub struct Division<T: MonomialOrdering> {
+    pub quotients: Vec<Polynomial<T>>,
+    pub remainder: Polynomial<T>,
+}
+
+impl<T: MonomialOrdering> Polynomial<T> {
+    /// Full reduction modulo `basis`. The result is unique, and zero exactly
+    /// for the members of the ideal, if `basis` is a Gröbner basis.
+    pub fn normal_form(&self, basis: &[Polynomial<T>]) -> Polynomial<T> {
+        self.reduce(basis, Reduction::Full)
+    }
+
+    pub fn reduce(&self, basis: &[Polynomial<T>], mode: Reduction) -> Polynomial<T> {
+        let basis: Vec<_> = basis.iter().collect();
+        self.divide_refs(&basis, mode, None)
+    }
+
+    /// Like `reduce`, but also returns the cofactors of the basis elements.
+    pub fn divide(&self, basis: &[Polynomial<T>], mode: Reduction) -> Division<T> {
+        let refs: Vec<_> = basis.iter().collect();
+        let mut quotients = vec![Polynomial::zero(&self.ring); basis.len()];
+        let remainder = self.divide_refs(&refs, mode, Some(&mut quotients));
+        Division {
+            quotients,
+            remainder,
+        }
+    }
+
+    pub(crate) fn normal_form_refs(&self, basis: &[&Polynomial<T>]) -> Polynomial<T> {
+        self.divide_refs(basis, Reduction::Full, None)
+    }
+
+    /// Division by the first basis element whose leading monomial divides the
+    /// current term.
+    fn divide_refs(
+        &self,
+        basis: &[&Polynomial<T>],
+        mode: Reduction,
+        mut quotients: Option<&mut [Polynomial<T>]>,
+    ) -> Polynomial<T> {
+        let mut p = self.clone();
+        let mut rem = Polynomial::zero(&self.ring);
+        while !p.is_zero() {
+            let m = p.lm().clone();
+            match basis.iter().position(|g| g.lm().divides(&m)) {
+                Some(i) => {
+                    let t = &m / basis[i].lm();
+                    p += &(basis[i] * &t);
+                    if let Some(quotients) = quotients.as_deref_mut() {
+                        quotients[i] += &t;
+                    }
+                }
+                None if mode == Reduction::Top => return p,
+                None => {
+                    p += &m;
+                    rem += &m;
+                }
+            }
+        }
+        rem
+    }
+}
+
+impl<T: MonomialOrdering> Polynomial<T> {
+    /// The same polynomial over `ring`, which has the same variables under a
+    /// possibly different ordering.
+    pub fn convert<U: MonomialOrdering>(&self, ring: &RingRef<U>) -> Polynomial<U> {
+        assert_eq!(self.ring.gens(), ring.gens());
+        let mut res = Polynomial::zero(ring);
+        for vars in self.terms().filter_map(|m| m.vars()) {
+            res.mons.insert(Monomial::NonZero {
+                ring: ring.clone(),
+                vars: vars.clone(),
+            });
+        }
+        res
+    }
+
+    /// Variables occurring in some term.
+    pub fn variables(&self) -> VarSet {
+        let mut set = VarSet::empty(self.ring.gens());
+        for vars in self.terms().filter_map(|m| m.vars()) {
+            set.union_with(vars);
+        }
+        set
+    }
+
+    /// Variables occurring in some term whose type satisfies `pattern`.
+    pub fn variables_matching(&self, pattern: impl Fn(AssociatedVariableType) -> bool) -> VarSet {
+        let mut set = self.variables();
+        set.difference_with(&self.ring.vars_matching(|t| !pattern(t)));
+        set
+    }
+
+    /// Splits `self` into `x_v * a + b` with `a` and `b` free of `x_v`.
+    pub fn split(&self, v: usize) -> (Polynomial<T>, Polynomial<T>) {
+        let mut a = Polynomial::zero(&self.ring);
+        let mut b = Polynomial::zero(&self.ring);
+        for m in self.terms() {
+            if m.contains(v) {
+                let mut q = m.clone();
+                if let Monomial::NonZero { vars, .. } = &mut q {
+                    vars.remove(v);
+                }
+                a.mons.insert(q);
+            } else {
+                b.mons.insert(m.clone());
+            }
+        }
+        (a, b)
+    }
+
+    /// Cofactor of `self` with `x_v` set to `value`.
+    pub fn fix(&self, v: usize, value: bool) -> Polynomial<T> {
+        let (a, b) = self.split(v);
+        if value {
+            a + b
+        } else {
+            b
+        }
+    }
+
+    /// Cofactor of `self` under a partial assignment, in one pass over the
+    /// terms.
+    pub fn fix_many(&self, assignment: &[(usize, bool)]) -> Polynomial<T> {
+        let mut zeros = VarSet::empty(self.ring.gens());
+        let mut ones = VarSet::empty(self.ring.gens());
+        for &(v, value) in assignment {
+            if value {
+                ones.insert(v);
+            } else {
+                zeros.insert(v);
+            }
+        }
+        let mut res = Polynomial::zero(&self.ring);
+        for m in self.terms() {
+            let Monomial::NonZero { vars, .. } = m else {
+                continue;
+            };
+            if !vars.is_disjoint(&zeros) {
+                continue;
+            }
+            let mut q = m.clone();
+            if let Monomial::NonZero { vars, .. } = &mut q {
+                vars.difference_with(&ones);
+            }
+            res += &q;
+        }
+        res
+    }
+
+    /// `self` with `x_v` replaced by `q`.
+    pub fn substitute(&self, v: usize, q: &Polynomial<T>) -> Polynomial<T> {
+        let (a, b) = self.split(v);
+        if a.is_zero() {
+            return b;
+        }
+        q * &a + b
+    }
+
+    /// Simultaneous substitution: `x_i` becomes `replacements[i]` where that
+    /// is set, all other variables stay.
+    pub fn compose(&self, replacements: &[Option<Polynomial<T>>]) -> Polynomial<T> {
+        let subs: Vec<(usize, &Polynomial<T>)> = replacements
+            .iter()
+            .enumerate()
+            .filter_map(|(i, r)| r.as_ref().map(|r| (i, r)))
+            .collect();
+        self.compose_split(&subs)
+    }
+
+    /// Horner scheme over the substituted variables, so the products of
+    /// replacements are shared between terms.
+    pub(crate) fn compose_split(&self, subs: &[(usize, &Polynomial<T>)]) -> Polynomial<T> {
+        let Some(((v, q), rest)) = subs.split_first() else {
+            return self.clone();
+        };
+        let (a, b) = self.split(*v);
+        let mut res = b.compose_split(rest);
+        if !a.is_zero() {
+            res += &(*q * &a.compose_split(rest));
+        }
+        res
+    }
+}
+
+impl<T: MonomialOrdering> Display for Polynomial<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if self.mons.is_empty() {
             write!(f, "0")
@@ -126,8 +348,8 @@ impl<'a, T: MonomialOrdering> Display for Polynomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> Add for Polynomial<'a, T> {
-    type Output = Polynomial<'a, T>;
+impl<T: MonomialOrdering> Add for Polynomial<T> {
+    type Output = Polynomial<T>;
     fn add(self, rhs: Self) -> Self {
         let mut new_pol = self;
         new_pol.add_assign(&rhs);
@@ -136,9 +358,9 @@ impl<'a, T: MonomialOrdering> Add for Polynomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> Add<&Polynomial<'a, T>> for Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Add<Polynomial<'a, T>>>::Output;
-    fn add(self, rhs: &'_ Self) -> Polynomial<'a, T> {
+impl<T: MonomialOrdering> Add<&Polynomial<T>> for Polynomial<T> {
+    type Output = <Polynomial<T> as Add<Polynomial<T>>>::Output;
+    fn add(self, rhs: &'_ Self) -> Polynomial<T> {
         let mut new_pol = self;
         new_pol.add_assign(rhs);
         new_pol.justify_lm();
@@ -146,9 +368,9 @@ impl<'a, T: MonomialOrdering> Add<&Polynomial<'a, T>> for Polynomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> Add<&Polynomial<'a, T>> for &Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Add<Polynomial<'a, T>>>::Output;
-    fn add(self, rhs: &'_ Polynomial<'a, T>) -> Polynomial<'a, T> {
+impl<T: MonomialOrdering> Add<&Polynomial<T>> for &Polynomial<T> {
+    type Output = <Polynomial<T> as Add<Polynomial<T>>>::Output;
+    fn add(self, rhs: &'_ Polynomial<T>) -> Polynomial<T> {
         let mut res_pol = self.clone();
         res_pol.add_assign(rhs);
         res_pol.justify_lm();
@@ -156,9 +378,9 @@ impl<'a, T: MonomialOrdering> Add<&Polynomial<'a, T>> for &Polynomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> Add<Polynomial<'a, T>> for &Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Add<Polynomial<'a, T>>>::Output;
-    fn add(self, rhs: Polynomial<'a, T>) -> Polynomial<'a, T> {
+impl<T: MonomialOrdering> Add<Polynomial<T>> for &Polynomial<T> {
+    type Output = <Polynomial<T> as Add<Polynomial<T>>>::Output;
+    fn add(self, rhs: Polynomial<T>) -> Polynomial<T> {
         let mut res_pol = self.clone();
         res_pol.add_assign(&rhs);
         res_pol.justify_lm();
@@ -166,9 +388,9 @@ impl<'a, T: MonomialOrdering> Add<Polynomial<'a, T>> for &Polynomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> Add<Monomial<'a, T>> for Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Add<Polynomial<'a, T>>>::Output;
-    fn add(self, rhs: Monomial<'a, T>) -> Polynomial<'a, T> {
+impl<T: MonomialOrdering> Add<Monomial<T>> for Polynomial<T> {
+    type Output = <Polynomial<T> as Add<Polynomial<T>>>::Output;
+    fn add(self, rhs: Monomial<T>) -> Polynomial<T> {
         if !rhs.is_zero() {
             let mut new_pol = self;
             new_pol.add_assign(&rhs);
@@ -178,9 +400,9 @@ impl<'a, T: MonomialOrdering> Add<Monomial<'a, T>> for Polynomial<'a, T> {
         }
     }
 }
-impl<'a, T: MonomialOrdering> Add<&Monomial<'a, T>> for Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Add<Polynomial<'a, T>>>::Output;
-    fn add(self, rhs: &'_ Monomial<'a, T>) -> Polynomial<'a, T> {
+impl<T: MonomialOrdering> Add<&Monomial<T>> for Polynomial<T> {
+    type Output = <Polynomial<T> as Add<Polynomial<T>>>::Output;
+    fn add(self, rhs: &'_ Monomial<T>) -> Polynomial<T> {
         if !rhs.is_zero() {
             let mut new_pol = self;
             new_pol.add_assign(rhs);
@@ -191,9 +413,9 @@ impl<'a, T: MonomialOrdering> Add<&Monomial<'a, T>> for Polynomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> Add<Monomial<'a, T>> for &Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Add<Polynomial<'a, T>>>::Output;
-    fn add(self, rhs: Monomial<'a, T>) -> Polynomial<'a, T> {
+impl<T: MonomialOrdering> Add<Monomial<T>> for &Polynomial<T> {
+    type Output = <Polynomial<T> as Add<Polynomial<T>>>::Output;
+    fn add(self, rhs: Monomial<T>) -> Polynomial<T> {
         if !rhs.is_zero() {
             let mut res_pol = self.clone();
             if !res_pol.mons.insert(rhs.clone()) {
@@ -207,9 +429,9 @@ impl<'a, T: MonomialOrdering> Add<Monomial<'a, T>> for &Polynomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> Add<&Monomial<'a, T>> for &Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Add<Polynomial<'a, T>>>::Output;
-    fn add(self, rhs: &'_ Monomial<'a, T>) -> Polynomial<'a, T> {
+impl<T: MonomialOrdering> Add<&Monomial<T>> for &Polynomial<T> {
+    type Output = <Polynomial<T> as Add<Polynomial<T>>>::Output;
+    fn add(self, rhs: &'_ Monomial<T>) -> Polynomial<T> {
         if !rhs.is_zero() {
             let mut res_pol = self.clone();
             if !res_pol.mons.insert(rhs.clone()) {
@@ -223,8 +445,8 @@ impl<'a, T: MonomialOrdering> Add<&Monomial<'a, T>> for &Polynomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> AddAssign<&Monomial<'a, T>> for Polynomial<'a, T> {
-    fn add_assign(&mut self, m: &'_ Monomial<'a, T>) {
+impl<T: MonomialOrdering> AddAssign<&Monomial<T>> for Polynomial<T> {
+    fn add_assign(&mut self, m: &'_ Monomial<T>) {
         if !m.is_zero() {
             if !self.mons.insert(m.clone()) {
                 self.mons.remove(m);
@@ -234,7 +456,7 @@ impl<'a, T: MonomialOrdering> AddAssign<&Monomial<'a, T>> for Polynomial<'a, T>
     }
 }
 
-impl<'a, T: MonomialOrdering> AddAssign<&Polynomial<'a, T>> for Polynomial<'a, T> {
+impl<T: MonomialOrdering> AddAssign<&Polynomial<T>> for Polynomial<T> {
     fn add_assign(&mut self, rhs: &'_ Self) {
         // for m in rhs.mons.iter() {
         //     self.mons.insert(m.clone());
@@ -249,10 +471,10 @@ impl<'a, T: MonomialOrdering> AddAssign<&Polynomial<'a, T>> for Polynomial<'a, T
     }
 }
 
-impl<'a, T: MonomialOrdering> AddAssign<u64> for Polynomial<'a, T> {
+impl<T: MonomialOrdering> AddAssign<u64> for Polynomial<T> {
     fn add_assign(&mut self, rhs: u64) {
         if rhs % 2 == 1 {
-            let one = Monomial::one(self.ring);
+            let one = Monomial::one(&self.ring);
             if self.mons.contains(&one) {
                 self.mons.remove(&one);
             } else {
@@ -263,11 +485,11 @@ impl<'a, T: MonomialOrdering> AddAssign<u64> for Polynomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> Add<u64> for &Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Add<Polynomial<'a, T>>>::Output;
-    fn add(self, rhs: u64) -> Polynomial<'a, T> {
+impl<T: MonomialOrdering> Add<u64> for &Polynomial<T> {
+    type Output = <Polynomial<T> as Add<Polynomial<T>>>::Output;
+    fn add(self, rhs: u64) -> Polynomial<T> {
         if rhs % 2 == 1 {
-            let one = Monomial::one(self.ring);
+            let one = Monomial::one(&self.ring);
             let mut res_pol = self.clone();
             if res_pol.mons.contains(&one) {
                 res_pol.mons.remove(&one);
@@ -281,11 +503,11 @@ impl<'a, T: MonomialOrdering> Add<u64> for &Polynomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> Add<u64> for Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Add<Polynomial<'a, T>>>::Output;
-    fn add(mut self, rhs: u64) -> Polynomial<'a, T> {
+impl<T: MonomialOrdering> Add<u64> for Polynomial<T> {
+    type Output = <Polynomial<T> as Add<Polynomial<T>>>::Output;
+    fn add(mut self, rhs: u64) -> Polynomial<T> {
         if rhs % 2 == 1 {
-            let one = Monomial::one(self.ring);
+            let one = Monomial::one(&self.ring);
             if self.mons.contains(&one) {
                 self.mons.remove(&one);
             } else {
@@ -296,33 +518,33 @@ impl<'a, T: MonomialOrdering> Add<u64> for Polynomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> AddAssign for Polynomial<'a, T> {
+impl<T: MonomialOrdering> AddAssign for Polynomial<T> {
     fn add_assign(&mut self, rhs: Self) {
         self.add_assign(&rhs);
         self.justify_lm();
     }
 }
 
-impl<'a, T: MonomialOrdering> Mul for Polynomial<'a, T> {
-    type Output = Polynomial<'a, T>;
+impl<T: MonomialOrdering> Mul for Polynomial<T> {
+    type Output = Polynomial<T>;
     fn mul(self, rhs: Self) -> Self {
         self.mul(&rhs)
     }
 }
 
-impl<'a, T: MonomialOrdering> Mul<&Polynomial<'a, T>> for Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Mul<Polynomial<'a, T>>>::Output;
+impl<T: MonomialOrdering> Mul<&Polynomial<T>> for Polynomial<T> {
+    type Output = <Polynomial<T> as Mul<Polynomial<T>>>::Output;
     fn mul(self, rhs: &'_ Self) -> Self {
         (&self).mul(rhs)
     }
 }
 
-impl<'a, T: MonomialOrdering> Mul<&Polynomial<'a, T>> for &Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Mul<Polynomial<'a, T>>>::Output;
-    fn mul(self, rhs: &Polynomial<'a, T>) -> Polynomial<'a, T> {
-        let mut res_pol = Polynomial::zero(self.ring);
+impl<T: MonomialOrdering> Mul<&Polynomial<T>> for &Polynomial<T> {
+    type Output = <Polynomial<T> as Mul<Polynomial<T>>>::Output;
+    fn mul(self, rhs: &Polynomial<T>) -> Polynomial<T> {
+        let mut res_pol = Polynomial::zero(&self.ring);
         if self.is_zero() || rhs.is_zero() {
-            return Polynomial::zero(self.ring);
+            return Polynomial::zero(&self.ring);
         }
         for m in rhs.mons.iter() {
             res_pol += self * m;
@@ -331,15 +553,15 @@ impl<'a, T: MonomialOrdering> Mul<&Polynomial<'a, T>> for &Polynomial<'a, T> {
         res_pol
     }
 }
-impl<'a, T: MonomialOrdering> Mul<&Monomial<'a, T>> for Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Mul<Polynomial<'a, T>>>::Output;
-    fn mul(self, rhs: &'_ Monomial<'a, T>) -> Polynomial<'a, T> {
+impl<T: MonomialOrdering> Mul<&Monomial<T>> for Polynomial<T> {
+    type Output = <Polynomial<T> as Mul<Polynomial<T>>>::Output;
+    fn mul(self, rhs: &'_ Monomial<T>) -> Polynomial<T> {
         if rhs.is_zero() {
-            Polynomial::zero(self.ring)
+            Polynomial::zero(&self.ring)
         } else if rhs.is_one() {
             self
         } else {
-            let mut new_pol = Polynomial::zero(self.ring);
+            let mut new_pol = Polynomial::zero(&self.ring);
             for m in self.mons.iter() {
                 let mon = m * rhs;
                 if !new_pol.mons.insert(mon.clone()) {
@@ -352,15 +574,15 @@ impl<'a, T: MonomialOrdering> Mul<&Monomial<'a, T>> for Polynomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> Mul<&Monomial<'a, T>> for &Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Mul<Polynomial<'a, T>>>::Output;
-    fn mul(self, rhs: &'_ Monomial<'a, T>) -> Polynomial<'a, T> {
+impl<T: MonomialOrdering> Mul<&Monomial<T>> for &Polynomial<T> {
+    type Output = <Polynomial<T> as Mul<Polynomial<T>>>::Output;
+    fn mul(self, rhs: &'_ Monomial<T>) -> Polynomial<T> {
         if rhs.is_zero() {
-            Polynomial::zero(self.ring)
+            Polynomial::zero(&self.ring)
         } else if rhs.is_one() {
             self.clone()
         } else {
-            let mut new_pol = Polynomial::zero(self.ring);
+            let mut new_pol = Polynomial::zero(&self.ring);
             for m in self.mons.iter() {
                 let mon = m * rhs;
                 if !new_pol.mons.insert(mon.clone()) {
@@ -373,26 +595,34 @@ impl<'a, T: MonomialOrdering> Mul<&Monomial<'a, T>> for &Polynomial<'a, T> {
     }
 }
 
-impl<'a, T: MonomialOrdering> Mul<Monomial<'a, T>> for Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Mul<Polynomial<'a, T>>>::Output;
-    fn mul(self, rhs: Monomial<'a, T>) -> Polynomial<'a, T> {
+impl<T: MonomialOrdering> Mul<Monomial<T>> for Polynomial<T> {
+    type Output = <Polynomial<T> as Mul<Polynomial<T>>>::Output;
+    fn mul(self, rhs: Monomial<T>) -> Polynomial<T> {
         self.mul(&rhs)
     }
 }
 
-impl<'a, T: MonomialOrdering> Mul<Monomial<'a, T>> for &Polynomial<'a, T> {
-    type Output = <Polynomial<'a, T> as Mul<Polynomial<'a, T>>>::Output;
-    fn mul(self, rhs: Monomial<'a, T>) -> Polynomial<'a, T> {
+impl<T: MonomialOrdering> Mul<Monomial<T>> for &Polynomial<T> {
+    type Output = <Polynomial<T> as Mul<Polynomial<T>>>::Output;
+    fn mul(self, rhs: Monomial<T>) -> Polynomial<T> {
         self.mul(&rhs)
     }
 }
 
+#[cfg(test)]
 mod tests {
-    use crate::{mon::Monomial, order::Lex, poly::Polynomial, ring::Ring};
+    use crate::{
+        mon::Monomial,
+        order::{DegLex, Lex},
+        poly::{Polynomial, Reduction},
+        ring::Ring,
+        testing::variables,
+        var::VarSet,
+    };
     #[test]
     fn mul() {
         let ring = Ring::<Lex>::new(8);
-        let ring = &Box::new(ring);
+        let ring = &ring.into_ref();
         let p1 = Polynomial::from_variable(ring, ring.var(0));
         let p2 = Polynomial::from_variable(ring, ring.var(1));
         assert_eq!("x_0*x_1", (p1 * p2).to_string());
@@ -402,7 +632,7 @@ mod tests {
     fn mul_mon() {
         let ordering = Lex;
         let ring = Ring::<Lex>::new(2);
-        let ring = &Box::new(ring);
+        let ring = &ring.into_ref();
         let mut p1 = Polynomial::from_variable(ring, ring.var(0));
         p1 += Polynomial::from_monomial(ring, Monomial::one(ring));
         let m: Monomial<_> = Monomial::from_variable(ring, ring.var(1));
@@ -412,7 +642,7 @@ mod tests {
     #[test]
     fn mul_poly() {
         let ring = Ring::<Lex>::new(8);
-        let ring = &Box::new(ring);
+        let ring = &ring.into_ref();
         let p1 = Polynomial::from_variable(ring, ring.var(0));
         let p2 = Polynomial::from_variable(ring, ring.var(1));
         assert_eq!("x_0*x_1 + x_0 + x_1 + 1", ((p1 + 1) * (p2 + 1)).to_string());
@@ -421,7 +651,7 @@ mod tests {
     #[test]
     fn mul_poly2() {
         let ring = Ring::<Lex>::new(8);
-        let ring = &Box::new(ring);
+        let ring = &ring.into_ref();
         let p1 = Polynomial::from_variable(ring, ring.var(0))
             + Polynomial::from_variable(ring, ring.var(1));
         let p2 = Polynomial::from_variable(ring, ring.var(2))
@@ -433,4 +663,105 @@ mod tests {
             ((p1 + 1) * (p2 + 1) * p3).to_string()
         );
     }
+
+    #[test]
+    fn normal_form() {
+        let ring = Ring::<Lex>::new(3).into_ref();
+        let x = variables(&ring);
+        let basis = [&x[0] + &x[1], &x[2] + 1];
+        let p = &x[0] + &x[2];
+        // x_0 -> x_1, then x_2 -> 1 in the tail
+        assert_eq!("x_1 + 1", p.normal_form(&basis).to_string());
+        assert_eq!("x_1 + x_2", p.reduce(&basis, Reduction::Top).to_string());
+    }
+
+    #[test]
+    fn division_certificate() {
+        let ring = Ring::<DegLex>::new(4).into_ref();
+        let x = variables(&ring);
+        let basis = [&x[0] * &x[1] + &x[2], &x[2] * &x[3] + &x[0] + 1];
+        let p = &x[0] * &x[1] * &x[3] + &x[1] * &x[2] + &x[3];
+        for mode in [Reduction::Full, Reduction::Top] {
+            let d = p.divide(&basis, mode);
+            let mut sum = d.remainder.clone();
+            for (q, g) in d.quotients.iter().zip(&basis) {
+                sum += &(q * g);
+            }
+            assert_eq!(p.to_string(), sum.to_string());
+        }
+        let d = p.divide(&basis, Reduction::Full);
+        assert_eq!(p.normal_form(&basis).to_string(), d.remainder.to_string());
+    }
+
+    #[test]
+    fn substitute() {
+        let ring = Ring::<Lex>::new(3).into_ref();
+        let x = variables(&ring);
+        let p = &x[0] * &x[1] + &x[2];
+        // (x_1 + 1) * x_1 = 0
+        assert_eq!("x_2", p.substitute(0, &(&x[1] + 1)).to_string());
+        assert_eq!("x_1*x_2 + x_2", p.substitute(0, &x[2]).to_string());
+        assert_eq!(p.to_string(), p.substitute(1, &x[1]).to_string());
+    }
+
+    #[test]
+    fn fix() {
+        let ring = Ring::<Lex>::new(4).into_ref();
+        let x = variables(&ring);
+        let p = &x[0] * &x[1] + &x[1] * &x[2] + &x[3] + 1;
+        assert_eq!("x_3 + 1", p.fix(1, false).to_string());
+        assert_eq!("x_0 + x_2 + x_3 + 1", p.fix(1, true).to_string());
+        let assignment = [(1, true), (2, true), (3, false)];
+        let mut q = p.clone();
+        for &(v, value) in &assignment {
+            q = q.fix(v, value);
+        }
+        assert_eq!("x_0", q.to_string());
+        assert_eq!(q.to_string(), p.fix_many(&assignment).to_string());
+    }
+
+    #[test]
+    fn compose_is_simultaneous() {
+        let ring = Ring::<Lex>::new(3).into_ref();
+        let x = variables(&ring);
+        let p = &x[0] + &x[1] * &x[2];
+        let swap = [Some(x[1].clone()), Some(x[0].clone()), None];
+        assert_eq!("x_0*x_2 + x_1", p.compose(&swap).to_string());
+        // a toy round: x_i <- x_i * x_{i+1} + x_{i+2}, unrolled four times
+        let round: Vec<_> = (0..3)
+            .map(|i| Some(&x[i] * &x[(i + 1) % 3] + &x[(i + 2) % 3]))
+            .collect();
+        let mut state = x.clone();
+        for _ in 0..4 {
+            state = state.iter().map(|s| s.compose(&round)).collect();
+        }
+        for bits in 0..8usize {
+            let mut a: Vec<bool> = (0..3).map(|i| bits >> i & 1 == 1).collect();
+            for _ in 0..4 {
+                a = (0..3)
+                    .map(|i| a[i] & a[(i + 1) % 3] ^ a[(i + 2) % 3])
+                    .collect();
+            }
+            let mut vars = VarSet::empty(3);
+            for i in (0..3).filter(|i| bits >> i & 1 == 1) {
+                vars.insert(i);
+            }
+            for i in 0..3 {
+                assert_eq!(a[i], state[i].eval(&vars));
+            }
+        }
+    }
+
+    #[test]
+    fn outlives_ring_binding() {
+        fn build() -> Polynomial<Lex> {
+            let ring = Ring::<Lex>::new(3).into_ref();
+            let x0 = Polynomial::from_variable(&ring, ring.var(0));
+            let x2 = Polynomial::from_variable(&ring, ring.var(2));
+            x0 * x2 + 1
+        }
+        let p = build();
+        let handle = std::thread::spawn(move || p.to_string());
+        assert_eq!("x_0*x_2 + 1", handle.join().unwrap());
+    }
 }
@@ -0,0 +1,246 @@
+use super::Polynomial;
+use crate::{
+    groebner::groebner_basis,
+    mon::Monomial,
+    order::{Block, Lex, MonomialOrdering},
+    ring::{Ring, RingRef},
+    var::VarSet,
+};
+use std::sync::OnceLock;
+
+/// Ideal of a Boolean ring given by generators, the field equations being
+/// implied. Its reduced Gröbner basis is computed on first use and kept.
+pub struct Ideal<T: MonomialOrdering> {
+    ring: RingRef<T>,
+    generators: Vec<Polynomial<T>>,
+    basis: OnceLock<Vec<Polynomial<T>>>,
+}
+
+impl<T: MonomialOrdering> Clone for Ideal<T> {
+    fn clone(&self) -> Self {
+        Ideal {
+            ring: self.ring.clone(),
+            generators: self.generators.clone(),
+            basis: self.basis.clone(),
+        }
+    }
+}
+
+/// `p` over `ring` with variable `v` renamed to `rename(v)`.
+fn rename<T: MonomialOrdering, U: MonomialOrdering>(
+    p: &Polynomial<T>,
+    ring: &RingRef<U>,
+    rename: impl Fn(usize) -> usize,
+) -> Polynomial<U> {
+    let mut res = Polynomial::zero(ring);
+    for vars in p.terms().filter_map(|m| m.vars()) {
+        let mut renamed = VarSet::empty(ring.gens());
+        for v in vars.iter() {
+            renamed.insert(rename(v));
+        }
+        res.mons.insert(Monomial::NonZero {
+            ring: ring.clone(),
+            vars: renamed,
+        });
+    }
+    res
+}
+
+impl<T: MonomialOrdering> Ideal<T> {
+    pub fn new(ring: &RingRef<T>, generators: Vec<Polynomial<T>>) -> Self {
+        Ideal {
+            ring: ring.clone(),
+            generators: generators.into_iter().filter(|p| !p.is_zero()).collect(),
+            basis: OnceLock::new(),
+        }
+    }
+
+    /// The zero ideal.
+    pub fn zero(ring: &RingRef<T>) -> Self {
+        Self::new(ring, Vec::new())
+    }
+
+    pub fn ring(&self) -> &RingRef<T> {
+        &self.ring
+    }
+
+    pub fn generators(&self) -> &[Polynomial<T>] {
+        &self.generators
+    }
+
+    /// Reduced Gröbner basis, as returned by `groebner_basis`.
+    pub fn basis(&self) -> &[Polynomial<T>] {
+        self.basis.get_or_init(|| {
+            if self.generators.is_empty() {
+                Vec::new()
+            } else {
+                groebner_basis(&self.generators)
+            }
+        })
+    }
+
+    pub fn contains(&self, p: &Polynomial<T>) -> bool {
+        p.normal_form(self.basis()).is_zero()
+    }
+
+    /// Whether `other` is contained in `self`.
+    pub fn contains_ideal(&self, other: &Ideal<T>) -> bool {
+        other.generators.iter().all(|g| self.contains(g))
+    }
+
+    pub fn sum(&self, other: &Ideal<T>) -> Ideal<T> {
+        let generators = self.generators.iter().chain(&other.generators).cloned();
+        Ideal::new(&self.ring, generators.collect())
+    }
+
+    pub fn product(&self, other: &Ideal<T>) -> Ideal<T> {
+        let mut generators = Vec::new();
+        for f in &self.generators {
+            for g in &other.generators {
+                generators.push(f * g);
+            }
+        }
+        Ideal::new(&self.ring, generators)
+    }
+
+    /// `I ∩ J`, by eliminating `t` from `t I + (t + 1) J`.
+    ///
+    /// Since `f = f^2` in a Boolean ring, this equals the product `I J`, but
+    /// its generators are far fewer.
+    pub fn intersection(&self, other: &Ideal<T>) -> Ideal<T> {
+        if self.generators.is_empty() || other.generators.is_empty() {
+            return Ideal::zero(&self.ring);
+        }
+        let n = self.ring.gens();
+        let mut ext = Ring::with_ordering(
+            n + 1,
+            Block::<Lex, T, 1>::new(Lex, self.ring.ordering().clone()),
+        );
+        ext.set_variable_name("t", 0);
+        for (i, v) in self.ring.variables().iter().enumerate() {
+            ext.set_variable_name(v.name(), i + 1);
+        }
+        let types = self.ring.variables().iter().enumerate();
+        ext.set_variable_types(types.map(|(i, v)| (v.associated_type(), i + 1)));
+        let ext = ext.into_ref();
+        let t = Polynomial::from_variable(&ext, ext.var(0));
+        let up = |p: &Polynomial<T>| rename(p, &ext, |v| v + 1);
+        let generators: Vec<_> = (self.generators.iter().map(|f| &t * &up(f)))
+            .chain(other.generators.iter().map(|g| (&t + 1) * &up(g)))
+            .collect();
+        // with t in the first block, the elements free of t generate the
+        // elimination ideal; the shifted indices may order them differently
+        // under T, so the basis is computed again
+        let generators = groebner_basis(&generators)
+            .iter()
+            .filter(|g| !g.variables().contains(0))
+            .map(|g| rename(g, &self.ring, |v| v - 1))
+            .collect();
+        Ideal::new(&self.ring, generators)
+    }
+
+    /// `I : f = { g : g f ∈ I }`, which is `I + <f + 1>` as `f` is
+    /// idempotent: `g = g f + g (f + 1)`.
+    pub fn quotient(&self, f: &Polynomial<T>) -> Ideal<T> {
+        let mut generators = self.generators.clone();
+        generators.push(f + 1);
+        Ideal::new(&self.ring, generators)
+    }
+}
+
+impl<T: MonomialOrdering> PartialEq for Ideal<T> {
+    /// Equal reduced Gröbner bases.
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (self.basis(), other.basis());
+        a.len() == b.len() && a.iter().zip(b).all(|(f, g)| f.terms().eq(g.terms()))
+    }
+}
+
+impl<T: MonomialOrdering> Eq for Ideal<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::Ideal;
+    use crate::{
+        order::{Block, DegLex, DynamicOrdering, MonomialOrdering},
+        ring::Ring,
+        system::is_solution,
+        testing::{assignments, variables},
+    };
+
+    /// Indices of the zeros of `ideal` among `assignments`.
+    fn variety<T: MonomialOrdering>(ideal: &Ideal<T>) -> Vec<usize> {
+        assignments(ideal.ring().gens())
+            .enumerate()
+            .filter(|(_, a)| is_solution(ideal.basis(), a))
+            .map(|(bits, _)| bits)
+            .collect()
+    }
+
+    #[test]
+    fn membership_and_equality() {
+        let ring = Ring::<DegLex>::new(3).into_ref();
+        let x = variables(&ring);
+        let i = Ideal::new(&ring, vec![&x[0] * &x[1] + &x[2], &x[1] + 1]);
+        assert!(i.contains(&(&x[0] + &x[2])));
+        assert!(!i.contains(&x[0]));
+        let j = Ideal::new(&ring, vec![&x[0] + &x[2], &x[1] + 1]);
+        assert!(i == j);
+        assert!(i != Ideal::new(&ring, vec![&x[1] + 1]));
+        assert!(Ideal::new(&ring, vec![&x[1] + 1]).contains_ideal(&Ideal::zero(&ring)));
+    }
+
+    #[test]
+    fn operations_match_varieties() {
+        let n = 4;
+        let ring = Ring::<DegLex>::new(n).into_ref();
+        let x = variables(&ring);
+        let i = Ideal::new(&ring, vec![&x[0] * &x[1] + &x[2], &x[3] + &x[1]]);
+        let j = Ideal::new(&ring, vec![&x[0] + &x[2] * &x[3] + 1]);
+        let (vi, vj) = (variety(&i), variety(&j));
+
+        let union: Vec<_> = (0..1 << n)
+            .filter(|p| vi.contains(p) || vj.contains(p))
+            .collect();
+        let meet = i.intersection(&j);
+        assert_eq!(union, variety(&meet));
+        assert!(meet == i.product(&j));
+        assert!(i.contains_ideal(&meet) && j.contains_ideal(&meet));
+
+        let common: Vec<_> = vi.iter().copied().filter(|p| vj.contains(p)).collect();
+        assert_eq!(common, variety(&i.sum(&j)));
+
+        // V(I : f) is V(I) without the zeros of f
+        let f = &x[0] + &x[3];
+        let outside: Vec<_> = vi
+            .iter()
+            .copied()
+            .filter(|p| (p & 1) != (p >> 3 & 1))
+            .collect();
+        assert_eq!(outside, variety(&i.quotient(&f)));
+        assert!(i.contains(&(&i.quotient(&f).generators()[0] * &f)));
+    }
+
+    #[test]
+    fn intersection_under_block_orderings() {
+        fn check<T: MonomialOrdering>(order: T) {
+            let n = 4;
+            let ring = Ring::with_ordering(n, order).into_ref();
+            let x = variables(&ring);
+            let i = Ideal::new(&ring, vec![&x[1] * &x[2] + &x[0], &x[3] + 1]);
+            let j = Ideal::new(&ring, vec![&x[0] * &x[3] + &x[1], &x[2] + &x[1]]);
+            let (vi, vj) = (variety(&i), variety(&j));
+            let union: Vec<_> = (0..1 << n)
+                .filter(|p| vi.contains(p) || vj.contains(p))
+                .collect();
+            let meet = i.intersection(&j);
+            assert_eq!(union, variety(&meet));
+            assert!(meet == i.product(&j));
+        }
+        // with t prepended the blocks split one variable later
+        check(Block::<DegLex, DegLex, 1>::default());
+        check(Block::<DegLex, DegLex, 2>::default());
+        check(DynamicOrdering::DegLexBlocks { split: 1 });
+        check(DynamicOrdering::DegLexBlocks { split: 3 });
+    }
+}
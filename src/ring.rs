@@ -1,38 +1,355 @@
-use std::{marker::PhantomData};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Display},
+    sync::{Arc, Mutex, MutexGuard},
+};
 
-use crate::var::Variable;
-use crate::{order::MonomialOrdering, var::AssociatedVariableType};
+use crate::var::{VarSet, Variable};
+use crate::{order::MonomialOrdering, var::AssociatedVariableType, zdd::ZddManager};
+
+/// Shared handle to a ring. Monomials and polynomials hold one, so the ring
+/// lives as long as anything built over it.
+pub type RingRef<T> = Arc<Ring<T>>;
 
-pub type BoxedRing<T> = Box<Ring<T>>;
 pub struct Ring<T: MonomialOrdering> {
     vars: Vec<Variable>,
-    order: PhantomData<T>,
+    /// Index of the variable of every associated type but `NoType`.
+    typed: HashMap<AssociatedVariableType, usize>,
+    zdd: Mutex<ZddManager>,
+    order: T,
 }
 
 impl<T: MonomialOrdering> Ring<T> {
     pub fn new(n: usize) -> Self {
+        Self::with_ordering(n, T::default())
+    }
+
+    /// Ring whose monomials are compared by `order`, which matters for
+    /// orderings chosen at runtime.
+    pub fn with_ordering(n: usize, mut order: T) -> Self {
         let mut vars = Vec::with_capacity(n);
         for i in 0..n {
             vars.push(Variable::new(format!("x_{}", i), i));
         }
+        order.prepare(&vars);
         Ring {
             vars,
-            order: PhantomData,
+            typed: HashMap::new(),
+            zdd: Mutex::new(ZddManager::new()),
+            order,
+        }
+    }
+
+    pub fn ordering(&self) -> &T {
+        &self.order
+    }
+
+    /// The same variables, names and types under another ordering; see
+    /// [`Polynomial::convert`](crate::poly::Polynomial::convert).
+    pub fn reordered<U: MonomialOrdering>(&self, mut order: U) -> Ring<U> {
+        order.prepare(&self.vars);
+        Ring {
+            vars: self.vars.clone(),
+            typed: self.typed.clone(),
+            zdd: Mutex::new(ZddManager::new()),
+            order,
         }
     }
     pub fn set_variable_name<S: ToString>(&mut self, name: S, order: usize) {
         self.vars[order].set_name(name);
     }
 
+    /// A variable that had `associated_type` before is left untyped.
     pub fn set_variable_type(&mut self, associated_type: AssociatedVariableType, order: usize) {
-        self.vars[order].set_associated_type(associated_type);
+        self.set_variable_types([(associated_type, order)]);
+    }
+
+    /// `set_variable_type` for several variables, preparing the ordering
+    /// only once.
+    pub fn set_variable_types<I>(&mut self, types: I)
+    where
+        I: IntoIterator<Item = (AssociatedVariableType, usize)>,
+    {
+        for (associated_type, order) in types {
+            let old = self.vars[order].associated_type();
+            if self.typed.get(&old) == Some(&order) {
+                self.typed.remove(&old);
+            }
+            if associated_type != AssociatedVariableType::NoType {
+                if let Some(holder) = self.typed.insert(associated_type, order) {
+                    self.vars[holder].set_associated_type(AssociatedVariableType::NoType);
+                }
+            }
+            self.vars[order].set_associated_type(associated_type);
+        }
+        self.order.prepare(&self.vars);
+    }
+
+    /// The variable of the given type, if the ring has one.
+    pub fn typed_var(&self, associated_type: AssociatedVariableType) -> Option<&Variable> {
+        self.typed.get(&associated_type).map(|&i| &self.vars[i])
+    }
+
+    fn expect_typed(&self, associated_type: AssociatedVariableType) -> &Variable {
+        self.typed_var(associated_type)
+            .unwrap_or_else(|| panic!("no variable of type {:?}", associated_type))
+    }
+
+    pub fn key_var(&self, i: usize) -> &Variable {
+        self.expect_typed(AssociatedVariableType::K(i))
+    }
+
+    pub fn x_var(&self, round: usize, bit: usize) -> &Variable {
+        self.expect_typed(AssociatedVariableType::X(round, bit))
+    }
+
+    pub fn y_var(&self, round: usize, bit: usize) -> &Variable {
+        self.expect_typed(AssociatedVariableType::Y(round, bit))
+    }
+
+    pub fn l_var(&self, i: usize, j: usize, k: usize) -> &Variable {
+        self.expect_typed(AssociatedVariableType::L(i, j, k))
+    }
+
+    /// All variables whose type satisfies `pattern`, e.g.
+    /// `|t| matches!(t, AssociatedVariableType::X(3, _))` for round 3.
+    pub fn vars_matching(&self, pattern: impl Fn(AssociatedVariableType) -> bool) -> VarSet {
+        let mut set = VarSet::empty(self.gens());
+        for (i, v) in self.vars.iter().enumerate() {
+            if pattern(v.associated_type()) {
+                set.insert(i);
+            }
+        }
+        set
+    }
+
+    pub fn variables(&self) -> &[Variable] {
+        &self.vars
     }
 
     pub fn var(&self, n: usize) -> &Variable {
         &self.vars[n]
     }
 
+    pub fn into_ref(self) -> RingRef<T> {
+        Arc::new(self)
+    }
+
     pub fn gens(&self) -> usize {
         self.vars.len()
     }
+
+    /// Node cache shared by all `ZddPolynomial`s of this ring.
+    pub fn zdd(&self) -> MutexGuard<'_, ZddManager> {
+        self.zdd.lock().unwrap()
+    }
+}
+
+/// Two variables of a `RingBuilder` share an associated type.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DuplicateVariableType(pub AssociatedVariableType);
+
+impl Display for DuplicateVariableType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "variable type `{:?}` is declared more than once", self.0)
+    }
+}
+
+impl std::error::Error for DuplicateVariableType {}
+
+/// Declares a ring block by block; variables are numbered in declaration order.
+///
+/// ```ignore
+/// let ring = RingBuilder::new().key(80).x(31, 64).y(31, 64).build::<DegLex>()?;
+/// let k5 = ring.key_var(5);
+/// ```
+#[derive(Default)]
+pub struct RingBuilder {
+    vars: Vec<(String, AssociatedVariableType)>,
+}
+
+impl RingBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// One untyped variable.
+    pub fn variable<S: ToString>(mut self, name: S) -> Self {
+        self.vars
+            .push((name.to_string(), AssociatedVariableType::NoType));
+        self
+    }
+
+    /// Key bits `k_0, ..., k_{n-1}`.
+    pub fn key(mut self, n: usize) -> Self {
+        for i in 0..n {
+            self.vars
+                .push((format!("k_{}", i), AssociatedVariableType::K(i)));
+        }
+        self
+    }
+
+    fn grid(
+        mut self,
+        prefix: &str,
+        rounds: usize,
+        bits: usize,
+        ty: fn(usize, usize) -> AssociatedVariableType,
+    ) -> Self {
+        for r in 0..rounds {
+            for b in 0..bits {
+                self.vars
+                    .push((format!("{}_{{{},{}}}", prefix, r, b), ty(r, b)));
+            }
+        }
+        self
+    }
+
+    /// State bits `x_{r,b}` for `rounds` rounds of `bits` bits, round-major.
+    pub fn x(self, rounds: usize, bits: usize) -> Self {
+        self.grid("x", rounds, bits, AssociatedVariableType::X)
+    }
+
+    /// State bits `y_{r,b}`, typically the S-box outputs of round `r`.
+    pub fn y(self, rounds: usize, bits: usize) -> Self {
+        self.grid("y", rounds, bits, AssociatedVariableType::Y)
+    }
+
+    /// Variables `l_{i,j,k}` for all indices below the given bounds.
+    pub fn l(mut self, ni: usize, nj: usize, nk: usize) -> Self {
+        for i in 0..ni {
+            for j in 0..nj {
+                for k in 0..nk {
+                    self.vars.push((
+                        format!("l_{{{},{},{}}}", i, j, k),
+                        AssociatedVariableType::L(i, j, k),
+                    ));
+                }
+            }
+        }
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.vars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    pub fn build<T: MonomialOrdering>(self) -> Result<Ring<T>, DuplicateVariableType> {
+        self.build_with(T::default())
+    }
+
+    pub fn build_with<T: MonomialOrdering>(
+        self,
+        order: T,
+    ) -> Result<Ring<T>, DuplicateVariableType> {
+        let mut seen = HashSet::new();
+        for &(_, ty) in &self.vars {
+            if ty != AssociatedVariableType::NoType && !seen.insert(ty) {
+                return Err(DuplicateVariableType(ty));
+            }
+        }
+        let mut ring = Ring::with_ordering(self.vars.len(), order);
+        let mut types = Vec::with_capacity(self.vars.len());
+        for (i, (name, ty)) in self.vars.into_iter().enumerate() {
+            ring.set_variable_name(name, i);
+            types.push((ty, i));
+        }
+        ring.set_variable_types(types);
+        Ok(ring)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DuplicateVariableType, RingBuilder};
+    use crate::{
+        order::DegLex,
+        poly::Polynomial,
+        system::{polys_touching, polys_within, system_variables},
+        var::{AssociatedVariableType, Variable},
+    };
+
+    #[test]
+    fn structured_ring() {
+        let ring = RingBuilder::new()
+            .key(4)
+            .x(2, 3)
+            .y(2, 3)
+            .variable("t")
+            .build::<DegLex>()
+            .unwrap();
+        assert_eq!(17, ring.gens());
+        assert_eq!("k_3", ring.key_var(3).name());
+        assert_eq!(3, ring.key_var(3).order());
+        assert_eq!("x_{1,2}", ring.x_var(1, 2).name());
+        assert_eq!(9, ring.x_var(1, 2).order());
+        assert_eq!(
+            AssociatedVariableType::Y(0, 1),
+            ring.y_var(0, 1).associated_type()
+        );
+        assert_eq!("t", ring.var(16).name());
+        assert!(ring.typed_var(AssociatedVariableType::K(4)).is_none());
+    }
+
+    #[test]
+    fn queries_by_type() {
+        let ring = RingBuilder::new()
+            .key(2)
+            .x(2, 2)
+            .y(2, 2)
+            .build::<DegLex>()
+            .unwrap()
+            .into_ref();
+        let round1 = ring.vars_matching(|t| matches!(t, AssociatedVariableType::X(1, _)));
+        assert_eq!(vec![4, 5], round1.iter().collect::<Vec<_>>());
+        let keys = ring.vars_matching(|t| matches!(t, AssociatedVariableType::K(_)));
+        let var = |v: &Variable| Polynomial::from_variable(&ring, v);
+        let f = [
+            var(ring.key_var(0)) * var(ring.key_var(1)) + 1,
+            var(ring.x_var(0, 1)) + var(ring.key_var(1)) + var(ring.y_var(1, 0)),
+        ];
+        assert_eq!(1, polys_within(&f, &keys).len());
+        assert_eq!(2, polys_touching(&f, &keys).len());
+        let state = f[1].variables_matching(|t| {
+            matches!(
+                t,
+                AssociatedVariableType::X(..) | AssociatedVariableType::Y(..)
+            )
+        });
+        assert_eq!(vec![3, 8], state.iter().collect::<Vec<_>>());
+        assert_eq!(4, system_variables(&f).len());
+    }
+
+    #[test]
+    fn retyping_updates_lookup() {
+        let mut ring = RingBuilder::new().key(2).build::<DegLex>().unwrap();
+        ring.set_variable_type(AssociatedVariableType::X(0, 0), 1);
+        assert!(ring.typed_var(AssociatedVariableType::K(1)).is_none());
+        assert_eq!(1, ring.x_var(0, 0).order());
+        assert_eq!(0, ring.key_var(0).order());
+        ring.set_variable_type(AssociatedVariableType::X(0, 0), 0);
+        assert_eq!(0, ring.x_var(0, 0).order());
+        assert_eq!(
+            AssociatedVariableType::NoType,
+            ring.var(1).associated_type()
+        );
+        assert!(ring.typed_var(AssociatedVariableType::K(0)).is_none());
+    }
+
+    #[test]
+    fn duplicate_types_are_rejected() {
+        let builder = RingBuilder::new().key(2).x(1, 2).variable("t").x(1, 1);
+        let err = builder.build::<DegLex>().err().unwrap();
+        assert_eq!(DuplicateVariableType(AssociatedVariableType::X(0, 0)), err);
+        assert_eq!(
+            "variable type `X(0, 0)` is declared more than once",
+            err.to_string()
+        );
+        let untyped = RingBuilder::new().variable("t").variable("u");
+        assert!(untyped.build::<DegLex>().is_ok());
+    }
 }
@@ -0,0 +1,716 @@
+use crate::{
+    cnf::{anf_to_cnf, CnfOptions},
+    order::MonomialOrdering,
+    poly::Polynomial,
+    var::VarSet,
+};
+
+/// Outcome of `solve`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SatResult {
+    /// Assignment of the ring variables at which every polynomial vanishes.
+    Sat(VarSet),
+    Unsat,
+    /// The conflict budget ran out.
+    Unknown,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SolverOptions {
+    /// Gives up with `SatResult::Unknown` after this many conflicts.
+    pub max_conflicts: Option<u64>,
+    /// Learned clauses kept before the first reduction of the clause
+    /// database, which grows by a tenth after each one. Defaults to a third
+    /// of the input clauses, but at least 2000.
+    pub max_learned: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolverStats {
+    pub decisions: u64,
+    pub propagations: u64,
+    pub conflicts: u64,
+    pub restarts: u64,
+    pub learned_clauses: u64,
+    pub deleted_clauses: u64,
+    /// Literals implied by the eliminated XOR system.
+    pub xor_propagations: u64,
+    /// Conflicts found by the eliminated XOR system.
+    pub xor_conflicts: u64,
+}
+
+/// `2 * var + negated`.
+type Lit = usize;
+
+fn lit(dimacs: i64) -> Lit {
+    2 * (dimacs.unsigned_abs() as usize - 1) + (dimacs < 0) as usize
+}
+
+fn var(l: Lit) -> usize {
+    l >> 1
+}
+
+/// `x_1 + x_5 + 1` and friends, as a bitset over all solver variables.
+#[derive(Default)]
+struct Row {
+    bits: Vec<u64>,
+    rhs: bool,
+    /// Unassigned variable eliminated from all other rows.
+    pivot: Option<usize>,
+    /// Another unassigned variable, so the row is only looked at again
+    /// once one of the two is assigned.
+    watch: Option<usize>,
+    queued: bool,
+}
+
+impl Row {
+    fn get(&self, v: usize) -> bool {
+        self.bits[v / 64] >> (v % 64) & 1 == 1
+    }
+
+    fn add(&mut self, other: &Row) {
+        for (a, b) in self.bits.iter_mut().zip(&other.bits) {
+            *a ^= b;
+        }
+        self.rhs ^= other.rhs;
+    }
+
+    fn vars(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bits.iter().enumerate().flat_map(|(i, &w)| {
+            (0..64)
+                .filter(move |b| w >> b & 1 == 1)
+                .map(move |b| 64 * i + b)
+        })
+    }
+
+    /// Whether the assigned variables add up to something else than the
+    /// right hand side.
+    fn parity(&self, assigns: &[Option<bool>]) -> bool {
+        self.vars()
+            .filter_map(|v| assigns[v])
+            .fold(self.rhs, |p, b| p ^ b)
+    }
+}
+
+/// Why a literal was implied, or which constraint is violated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Reason {
+    Clause(usize),
+    /// An XOR row, turned into a clause only when it is analyzed.
+    Xor(usize),
+}
+
+enum Gauss {
+    Fixpoint,
+    Propagated,
+    Conflict(usize),
+}
+
+/// 1, 1, 2, 1, 1, 2, 4, ...
+fn luby(mut i: u64) -> u64 {
+    let (mut size, mut seq) = (1, 0);
+    while size < i + 1 {
+        seq += 1;
+        size = 2 * size + 1;
+    }
+    while size - 1 != i {
+        size = (size - 1) >> 1;
+        seq -= 1;
+        i %= size;
+    }
+    1 << seq
+}
+
+struct Solver {
+    clauses: Vec<Vec<Lit>>,
+    /// Clauses watching each literal, visited when it becomes false.
+    watches: Vec<Vec<usize>>,
+    xors: Vec<Row>,
+    /// Rows whose pivot or watch is each variable, possibly stale.
+    xor_watches: Vec<Vec<usize>>,
+    /// Rows to look at before the XOR system is at a fixpoint.
+    xor_queue: Vec<usize>,
+    /// Rows with fewer than two unassigned variables, which a backtrack
+    /// may free.
+    xor_idle: Vec<usize>,
+    /// Trail position up to which assignments were seen by the rows.
+    xor_head: usize,
+    assigns: Vec<Option<bool>>,
+    level: Vec<usize>,
+    reason: Vec<Option<Reason>>,
+    trail: Vec<Lit>,
+    trail_lim: Vec<usize>,
+    qhead: usize,
+    activity: Vec<f64>,
+    var_inc: f64,
+    phase: Vec<bool>,
+    seen: Vec<bool>,
+    stats: SolverStats,
+}
+
+impl Solver {
+    fn new(vars: usize) -> Self {
+        Solver {
+            clauses: Vec::new(),
+            watches: vec![Vec::new(); 2 * vars],
+            xors: Vec::new(),
+            xor_watches: vec![Vec::new(); vars],
+            xor_queue: Vec::new(),
+            xor_idle: Vec::new(),
+            xor_head: 0,
+            assigns: vec![None; vars],
+            level: vec![0; vars],
+            reason: vec![None; vars],
+            trail: Vec::new(),
+            trail_lim: Vec::new(),
+            qhead: 0,
+            activity: vec![0.0; vars],
+            var_inc: 1.0,
+            phase: vec![false; vars],
+            seen: vec![false; vars],
+            stats: SolverStats::default(),
+        }
+    }
+
+    fn value(&self, l: Lit) -> Option<bool> {
+        self.assigns[var(l)].map(|b| b != (l & 1 == 1))
+    }
+
+    fn decision_level(&self) -> usize {
+        self.trail_lim.len()
+    }
+
+    fn enqueue(&mut self, l: Lit, reason: Option<Reason>) {
+        let v = var(l);
+        self.assigns[v] = Some(l & 1 == 0);
+        self.level[v] = self.decision_level();
+        self.reason[v] = reason;
+        self.trail.push(l);
+        self.stats.propagations += 1;
+    }
+
+    /// Stores a clause whose first two literals are the ones to watch.
+    fn attach(&mut self, clause: Vec<Lit>) -> usize {
+        let ci = self.clauses.len();
+        if clause.len() >= 2 {
+            self.watches[clause[0]].push(ci);
+            self.watches[clause[1]].push(ci);
+        }
+        self.clauses.push(clause);
+        ci
+    }
+
+    /// Adds an input clause at level 0, `false` if it is already violated.
+    fn add_clause(&mut self, mut clause: Vec<Lit>) -> bool {
+        clause.sort_unstable();
+        clause.dedup();
+        if clause.windows(2).any(|w| w[0] ^ 1 == w[1]) {
+            return true;
+        }
+        match clause.len() {
+            0 => false,
+            1 => match self.value(clause[0]) {
+                Some(b) => b,
+                None => {
+                    self.enqueue(clause[0], None);
+                    true
+                }
+            },
+            _ => {
+                self.attach(clause);
+                true
+            }
+        }
+    }
+
+    fn propagate(&mut self) -> Option<usize> {
+        while self.qhead < self.trail.len() {
+            let false_lit = self.trail[self.qhead] ^ 1;
+            self.qhead += 1;
+            let watching = std::mem::take(&mut self.watches[false_lit]);
+            let mut kept = Vec::with_capacity(watching.len());
+            let mut conflict = None;
+            for (i, &ci) in watching.iter().enumerate() {
+                if conflict.is_some() {
+                    kept.extend_from_slice(&watching[i..]);
+                    break;
+                }
+                if self.clauses[ci][0] == false_lit {
+                    self.clauses[ci].swap(0, 1);
+                }
+                let first = self.clauses[ci][0];
+                if self.value(first) == Some(true) {
+                    kept.push(ci);
+                    continue;
+                }
+                let replacement = (2..self.clauses[ci].len())
+                    .find(|&k| self.value(self.clauses[ci][k]) != Some(false));
+                if let Some(k) = replacement {
+                    self.clauses[ci].swap(1, k);
+                    let watch = self.clauses[ci][1];
+                    self.watches[watch].push(ci);
+                    continue;
+                }
+                kept.push(ci);
+                if self.value(first) == Some(false) {
+                    conflict = Some(ci);
+                } else {
+                    self.enqueue(first, Some(Reason::Clause(ci)));
+                }
+            }
+            self.watches[false_lit] = kept;
+            if conflict.is_some() {
+                return conflict;
+            }
+        }
+        None
+    }
+
+    /// Literal of `v` that is false under the current assignment.
+    fn false_lit(&self, v: usize) -> Lit {
+        2 * v + self.assigns[v].unwrap() as usize
+    }
+
+    fn add_xor(&mut self, row: Row) {
+        self.xors.push(row);
+        self.queue_xor(self.xors.len() - 1);
+    }
+
+    fn queue_xor(&mut self, r: usize) {
+        if !self.xors[r].queued {
+            self.xors[r].queued = true;
+            self.xor_queue.push(r);
+        }
+    }
+
+    /// Keeps the XOR rows Gauss–Jordan eliminated on the unassigned
+    /// variables. A row is only looked at again once its pivot or watch is
+    /// assigned, another row is added to it, or a backtrack may have freed
+    /// variables of a row that had fewer than two. Rows left with a single
+    /// unassigned variable imply it, rows left with none may be violated.
+    /// Either way the row is the reason, and it stays unchanged as long as
+    /// all its variables are assigned.
+    fn gauss(&mut self) -> Gauss {
+        while self.xor_head < self.trail.len() {
+            let v = var(self.trail[self.xor_head]);
+            self.xor_head += 1;
+            for r in std::mem::take(&mut self.xor_watches[v]) {
+                let row = &mut self.xors[r];
+                if row.pivot == Some(v) {
+                    row.pivot = None;
+                } else if row.watch == Some(v) {
+                    row.watch = None;
+                } else {
+                    continue;
+                }
+                self.queue_xor(r);
+            }
+        }
+
+        let mut propagated = false;
+        while let Some(r) = self.xor_queue.pop() {
+            self.xors[r].queued = false;
+            if self.xors[r].pivot.is_none_or(|v| self.assigns[v].is_some()) {
+                let pivot = self.xors[r].vars().find(|&v| self.assigns[v].is_none());
+                self.xors[r].pivot = pivot;
+                if let Some(v) = pivot {
+                    self.xor_watches[v].push(r);
+                    // the other variables of a row are no pivots, so
+                    // pivoting on any of them leaves the remaining pivots
+                    // alone
+                    let row = std::mem::take(&mut self.xors[r]);
+                    for q in 0..self.xors.len() {
+                        if q != r && self.xors[q].get(v) {
+                            self.xors[q].add(&row);
+                            self.queue_xor(q);
+                        }
+                    }
+                    self.xors[r] = row;
+                }
+            }
+            let row = &self.xors[r];
+            let Some(pivot) = row.pivot else {
+                self.xor_idle.push(r);
+                if row.parity(&self.assigns) {
+                    self.stats.xor_conflicts += 1;
+                    return Gauss::Conflict(r);
+                }
+                continue;
+            };
+            let free = |v: usize| v != pivot && self.assigns[v].is_none();
+            if row.watch.is_some_and(|v| row.get(v) && free(v)) {
+                continue;
+            }
+            let watch = row.vars().find(|&v| free(v));
+            self.xors[r].watch = watch;
+            if let Some(v) = watch {
+                self.xor_watches[v].push(r);
+                continue;
+            }
+            self.xor_idle.push(r);
+            let parity = self.xors[r].parity(&self.assigns);
+            self.stats.xor_propagations += 1;
+            self.enqueue(2 * pivot + !parity as usize, Some(Reason::Xor(r)));
+            propagated = true;
+        }
+        if propagated {
+            Gauss::Propagated
+        } else {
+            Gauss::Fixpoint
+        }
+    }
+
+    /// Literals of `reason` under the current assignment, the one implied
+    /// for `implied` first.
+    fn reason_lits(&self, reason: Reason, implied: Option<usize>, lits: &mut Vec<Lit>) {
+        lits.clear();
+        match reason {
+            Reason::Clause(ci) => lits.extend_from_slice(&self.clauses[ci]),
+            Reason::Xor(r) => {
+                lits.extend(implied.map(|v| self.false_lit(v) ^ 1));
+                lits.extend(
+                    self.xors[r]
+                        .vars()
+                        .filter(|&v| Some(v) != implied)
+                        .map(|v| self.false_lit(v)),
+                );
+            }
+        }
+    }
+
+    fn propagate_all(&mut self) -> Option<Reason> {
+        loop {
+            if let Some(ci) = self.propagate() {
+                return Some(Reason::Clause(ci));
+            }
+            match self.gauss() {
+                Gauss::Fixpoint => return None,
+                Gauss::Propagated => continue,
+                Gauss::Conflict(r) => return Some(Reason::Xor(r)),
+            }
+        }
+    }
+
+    fn backtrack(&mut self, level: usize) {
+        if self.decision_level() <= level {
+            return;
+        }
+        let start = self.trail_lim[level];
+        for &l in &self.trail[start..] {
+            let v = var(l);
+            self.phase[v] = l & 1 == 0;
+            self.assigns[v] = None;
+            self.reason[v] = None;
+        }
+        self.trail.truncate(start);
+        self.trail_lim.truncate(level);
+        self.qhead = start;
+        self.xor_head = self.xor_head.min(start);
+        for r in std::mem::take(&mut self.xor_idle) {
+            self.queue_xor(r);
+        }
+    }
+
+    fn bump(&mut self, v: usize) {
+        self.activity[v] += self.var_inc;
+        if self.activity[v] > 1e100 {
+            for a in &mut self.activity {
+                *a *= 1e-100;
+            }
+            self.var_inc *= 1e-100;
+        }
+    }
+
+    /// First-UIP clause learned from a conflict at the current level,
+    /// asserting literal first and the literal of the backjump level second.
+    fn analyze(&mut self, conflict: Reason) -> Vec<Lit> {
+        let current = self.decision_level();
+        let mut learnt = vec![0];
+        let mut pending = 0;
+        let mut lits = Vec::new();
+        self.reason_lits(conflict, None, &mut lits);
+        let mut skip_first = false;
+        let mut idx = self.trail.len();
+        let asserting = loop {
+            for &q in &lits[skip_first as usize..] {
+                let v = var(q);
+                if self.seen[v] || self.level[v] == 0 {
+                    continue;
+                }
+                self.seen[v] = true;
+                self.bump(v);
+                if self.level[v] == current {
+                    pending += 1;
+                } else {
+                    learnt.push(q);
+                }
+            }
+            let p = loop {
+                idx -= 1;
+                if self.seen[var(self.trail[idx])] {
+                    break self.trail[idx];
+                }
+            };
+            self.seen[var(p)] = false;
+            pending -= 1;
+            if pending == 0 {
+                break p;
+            }
+            self.reason_lits(self.reason[var(p)].unwrap(), Some(var(p)), &mut lits);
+            skip_first = true;
+        };
+        learnt[0] = asserting ^ 1;
+        for &l in &learnt[1..] {
+            self.seen[var(l)] = false;
+        }
+        if let Some(k) = (1..learnt.len()).max_by_key(|&k| self.level[var(learnt[k])]) {
+            learnt.swap(1, k);
+        }
+        self.var_inc /= 0.95;
+        learnt
+    }
+
+    /// Deletes the longer half of the learned clauses, the ones from index
+    /// `first` on, except for binary clauses and reasons of assignments.
+    fn reduce_learned(&mut self, first: usize) {
+        let mut candidates: Vec<usize> = (first..self.clauses.len())
+            .filter(|&ci| {
+                let clause = &self.clauses[ci];
+                clause.len() > 2 && self.reason[var(clause[0])] != Some(Reason::Clause(ci))
+            })
+            .collect();
+        candidates.sort_by_key(|&ci| std::cmp::Reverse(self.clauses[ci].len()));
+        let mut deleted = vec![false; self.clauses.len()];
+        for &ci in &candidates[..candidates.len() / 2] {
+            deleted[ci] = true;
+        }
+        let mut index = vec![None; self.clauses.len()];
+        for (ci, clause) in std::mem::take(&mut self.clauses).into_iter().enumerate() {
+            if !deleted[ci] {
+                index[ci] = Some(self.clauses.len());
+                self.clauses.push(clause);
+            }
+        }
+        for reason in self.reason.iter_mut().flatten() {
+            if let Reason::Clause(ci) = reason {
+                *ci = index[*ci].unwrap();
+            }
+        }
+        for watching in &mut self.watches {
+            watching.retain_mut(|ci| match index[*ci] {
+                Some(new) => {
+                    *ci = new;
+                    true
+                }
+                None => false,
+            });
+        }
+        self.stats.deleted_clauses += (candidates.len() / 2) as u64;
+    }
+
+    fn pick_branch(&self) -> Option<usize> {
+        (0..self.assigns.len())
+            .filter(|&v| self.assigns[v].is_none())
+            .max_by(|&a, &b| self.activity[a].total_cmp(&self.activity[b]))
+    }
+
+    fn search(&mut self, options: &SolverOptions) -> Option<bool> {
+        let mut restart_at = 100 * luby(0);
+        let mut since_restart = 0;
+        let first_learned = self.clauses.len();
+        let mut max_learned = options.max_learned.unwrap_or((first_learned / 3).max(2000));
+        loop {
+            let Some(conflict) = self.propagate_all() else {
+                let Some(v) = self.pick_branch() else {
+                    return Some(true);
+                };
+                self.stats.decisions += 1;
+                self.trail_lim.push(self.trail.len());
+                self.enqueue(2 * v + !self.phase[v] as usize, None);
+                continue;
+            };
+            self.stats.conflicts += 1;
+            since_restart += 1;
+            // XOR conflicts may lie entirely below the current level
+            let mut lits = Vec::new();
+            self.reason_lits(conflict, None, &mut lits);
+            let level = lits.iter().map(|&l| self.level[var(l)]).max().unwrap_or(0);
+            if level == 0 {
+                return Some(false);
+            }
+            self.backtrack(level);
+            let learnt = self.analyze(conflict);
+            let back = learnt.get(1).map_or(0, |&l| self.level[var(l)]);
+            self.backtrack(back);
+            let asserting = learnt[0];
+            if learnt.len() == 1 {
+                self.enqueue(asserting, None);
+            } else {
+                self.stats.learned_clauses += 1;
+                let ci = self.attach(learnt);
+                self.enqueue(asserting, Some(Reason::Clause(ci)));
+            }
+            if self.clauses.len() - first_learned > max_learned {
+                self.reduce_learned(first_learned);
+                max_learned += max_learned / 10;
+            }
+            if options
+                .max_conflicts
+                .is_some_and(|max| self.stats.conflicts >= max)
+            {
+                return None;
+            }
+            if since_restart >= restart_at {
+                self.stats.restarts += 1;
+                since_restart = 0;
+                restart_at = 100 * luby(self.stats.restarts);
+                self.backtrack(0);
+            }
+        }
+    }
+}
+
+/// Searches for a common zero of `polys`.
+///
+/// Nonlinear monomials are encoded as clauses as in `anf_to_cnf`, the
+/// polynomials themselves stay XOR rows that are kept Gauss–Jordan
+/// eliminated against the current assignment during the search.
+pub fn solve<T: MonomialOrdering>(polys: &[Polynomial<T>], options: &SolverOptions) -> SatResult {
+    solve_with_stats(polys, options).0
+}
+
+pub fn solve_with_stats<T: MonomialOrdering>(
+    polys: &[Polynomial<T>],
+    options: &SolverOptions,
+) -> (SatResult, SolverStats) {
+    let cnf = anf_to_cnf(
+        polys,
+        &CnfOptions {
+            cutting_length: usize::MAX,
+            xor_clauses: true,
+        },
+    );
+    let mut solver = Solver::new(cnf.vars);
+    let mut consistent = true;
+    for clause in &cnf.clauses {
+        consistent &= solver.add_clause(clause.iter().map(|&l| lit(l)).collect());
+    }
+    let words = cnf.vars.div_ceil(64);
+    for xor in &cnf.xors {
+        let mut row = Row {
+            bits: vec![0; words],
+            rhs: xor.rhs,
+            ..Default::default()
+        };
+        for &v in &xor.vars {
+            row.bits[(v - 1) / 64] ^= 1 << ((v - 1) % 64);
+        }
+        solver.add_xor(row);
+    }
+    let outcome = if consistent {
+        solver.search(options)
+    } else {
+        Some(false)
+    };
+    let result = match outcome {
+        Some(true) => {
+            let model: Vec<i64> = (0..cnf.vars)
+                .map(|v| {
+                    let l = v as i64 + 1;
+                    if solver.assigns[v] == Some(true) {
+                        l
+                    } else {
+                        -l
+                    }
+                })
+                .collect();
+            SatResult::Sat(cnf.assignment(&model))
+        }
+        Some(false) => SatResult::Unsat,
+        None => SatResult::Unknown,
+    };
+    (result, solver.stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{solve, solve_with_stats, SatResult, SolverOptions};
+    use crate::{
+        order::DegLex,
+        poly::Polynomial,
+        ring::Ring,
+        system::is_solution,
+        testing::{assignments, random_system, variables},
+    };
+
+    fn has_solution(f: &[Polynomial<DegLex>], n: usize) -> bool {
+        assignments(n).any(|a| is_solution(f, &a))
+    }
+
+    #[test]
+    fn agrees_with_exhaustive_search() {
+        let n = 8;
+        let ring = Ring::<DegLex>::new(n).into_ref();
+        for seed in 1..40 {
+            let f = random_system(&ring, 9, n, 2, seed);
+            match solve(&f, &SolverOptions::default()) {
+                SatResult::Sat(a) => assert!(is_solution(&f, &a)),
+                SatResult::Unsat => assert!(!has_solution(&f, n)),
+                SatResult::Unknown => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn linear_parts_are_eliminated() {
+        let ring = Ring::<DegLex>::new(4).into_ref();
+        let x = variables(&ring);
+        // the XOR of all three is 1 = 0, no clause sees that
+        let f = [
+            &x[0] * &x[1] + &x[2] + &x[3],
+            &x[2] + &x[0],
+            &x[0] * &x[1] + &x[3] + &x[0] + 1,
+        ];
+        let (result, stats) = solve_with_stats(&f, &SolverOptions::default());
+        assert_eq!(SatResult::Unsat, result);
+        assert_eq!(0, stats.decisions);
+        assert_eq!(1, stats.xor_conflicts);
+        assert_eq!(0, stats.learned_clauses);
+    }
+
+    #[test]
+    fn conflict_budget() {
+        let n = 14;
+        let ring = Ring::<DegLex>::new(n).into_ref();
+        let f = random_system(&ring, 16, 2 * n, 2, 7);
+        let options = SolverOptions {
+            max_conflicts: Some(2),
+            ..Default::default()
+        };
+        let (result, stats) = solve_with_stats(&f, &options);
+        assert_eq!(SatResult::Unknown, result);
+        assert_eq!(2, stats.conflicts);
+        let (complete, _) = solve_with_stats(&f, &SolverOptions::default());
+        assert_eq!(matches!(complete, SatResult::Sat(_)), has_solution(&f, n));
+    }
+
+    #[test]
+    fn learned_clauses_are_reduced() {
+        let n = 14;
+        let ring = Ring::<DegLex>::new(n).into_ref();
+        for seed in [3, 7, 10] {
+            let f = random_system(&ring, 16, 2 * n, 2, seed);
+            let options = SolverOptions {
+                max_learned: Some(10),
+                ..Default::default()
+            };
+            let (result, stats) = solve_with_stats(&f, &options);
+            assert!(stats.deleted_clauses > 0);
+            match result {
+                SatResult::Sat(a) => assert!(is_solution(&f, &a)),
+                SatResult::Unsat => assert!(!has_solution(&f, n)),
+                SatResult::Unknown => unreachable!(),
+            }
+        }
+    }
+}
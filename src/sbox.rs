@@ -0,0 +1,262 @@
+use std::collections::HashSet;
+
+use crate::{
+    linalg::Gf2Matrix, mon::Monomial, order::MonomialOrdering, poly::Polynomial, ring::RingRef,
+};
+
+/// Lookup table from `n` to `m` bits. Bit `i` of an index or entry belongs to
+/// the `i`-th input or output variable.
+pub struct Sbox {
+    table: Vec<u64>,
+    n: usize,
+    m: usize,
+}
+
+impl Sbox {
+    pub fn new(table: Vec<u64>, m: usize) -> Self {
+        assert!(table.len().is_power_of_two() && m <= 64);
+        let n = table.len().trailing_zeros() as usize;
+        assert!(table.iter().all(|&y| m == 64 || y >> m == 0));
+        Sbox { table, n, m }
+    }
+
+    pub fn input_bits(&self) -> usize {
+        self.n
+    }
+
+    pub fn output_bits(&self) -> usize {
+        self.m
+    }
+
+    fn distinct(vars: &[usize]) -> bool {
+        let mut seen = HashSet::new();
+        vars.iter().all(|v| seen.insert(v))
+    }
+
+    fn monomial<T: MonomialOrdering>(ring: &RingRef<T>, vars: &[usize], mask: u64) -> Monomial<T> {
+        vars.iter()
+            .enumerate()
+            .filter(|(i, _)| mask >> i & 1 == 1)
+            .fold(Monomial::one(ring), |m, (_, &v)| m * ring.var(v))
+    }
+
+    /// ANF coefficients of output bit `j`, indexed by the mask of the input
+    /// bits of each monomial, by the Möbius transform of its truth table.
+    fn coefficients(&self, j: usize) -> Vec<bool> {
+        let mut f: Vec<bool> = self.table.iter().map(|y| y >> j & 1 == 1).collect();
+        for i in 0..self.n {
+            for x in 0..f.len() {
+                if x >> i & 1 == 1 {
+                    f[x] ^= f[x ^ 1 << i];
+                }
+            }
+        }
+        f
+    }
+
+    /// ANF of every output bit in the ring variables `inputs`.
+    pub fn anf<T: MonomialOrdering>(
+        &self,
+        ring: &RingRef<T>,
+        inputs: &[usize],
+    ) -> Vec<Polynomial<T>> {
+        assert_eq!(self.n, inputs.len());
+        assert!(Self::distinct(inputs));
+        (0..self.m)
+            .map(|j| {
+                let mut p = Polynomial::zero(ring);
+                for (x, _) in self.coefficients(j).iter().enumerate().filter(|(_, &c)| c) {
+                    p += Polynomial::from_monomial(ring, Self::monomial(ring, inputs, x as u64));
+                }
+                p
+            })
+            .collect()
+    }
+
+    /// Output bits for arbitrary input polynomials, the ANF with `inputs`
+    /// substituted.
+    pub fn apply<T: MonomialOrdering>(&self, inputs: &[Polynomial<T>]) -> Vec<Polynomial<T>> {
+        assert_eq!(self.n, inputs.len());
+        let ring = inputs[0].ring();
+        // products[x] is the product of the inputs in the mask x
+        let mut products = vec![Polynomial::one(ring)];
+        for x in 1..self.table.len() {
+            let low = x.trailing_zeros() as usize;
+            let p = &products[x & (x - 1)] * &inputs[low];
+            products.push(p);
+        }
+        (0..self.m)
+            .map(|j| {
+                let mut p = Polynomial::zero(ring);
+                for (x, _) in self.coefficients(j).iter().enumerate().filter(|(_, &c)| c) {
+                    p += &products[x];
+                }
+                p
+            })
+            .collect()
+    }
+
+    /// Basis of all polynomials of degree at most `degree` in `inputs` and
+    /// `outputs` that vanish on every pair `(x, S(x))`.
+    ///
+    /// The relations come from the kernel of the evaluation matrix with the
+    /// monomials in increasing order, so their leading monomials are distinct
+    /// and appear in no other relation.
+    pub fn implicit_relations<T: MonomialOrdering>(
+        &self,
+        ring: &RingRef<T>,
+        inputs: &[usize],
+        outputs: &[usize],
+        degree: usize,
+    ) -> Vec<Polynomial<T>> {
+        assert_eq!(self.n, inputs.len());
+        assert_eq!(self.m, outputs.len());
+        let k = self.n + self.m;
+        assert!(k <= 64);
+        let vars: Vec<usize> = inputs.iter().chain(outputs).copied().collect();
+        assert!(Self::distinct(&vars));
+
+        let mut masks = vec![0u64];
+        let mut frontier = vec![0u64];
+        for _ in 0..degree.min(k) {
+            let mut next = Vec::new();
+            for &mask in &frontier {
+                let top = 64 - mask.leading_zeros() as usize;
+                for i in top..k {
+                    next.push(mask | 1 << i);
+                }
+            }
+            masks.extend_from_slice(&next);
+            frontier = next;
+        }
+        let mut columns: Vec<(Monomial<T>, u64)> = masks
+            .into_iter()
+            .map(|mask| (Self::monomial(ring, &vars, mask), mask))
+            .collect();
+        columns.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut matrix = Gf2Matrix::new(columns.len());
+        for (x, &y) in self.table.iter().enumerate() {
+            let point = x as u64 | if self.n == 64 { 0 } else { y << self.n };
+            matrix.push_row(
+                columns
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (_, mask))| mask & !point == 0)
+                    .map(|(c, _)| c),
+            );
+        }
+        let kernel = matrix.kernel();
+        (0..kernel.nrows())
+            .map(|r| {
+                let mut p = Polynomial::zero(ring);
+                for c in kernel.row(r) {
+                    p += Polynomial::from_monomial(ring, columns[c].0.clone());
+                }
+                p
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::Sbox;
+    use crate::{order::DegLex, ring::Ring, var::VarSet};
+
+    const PRESENT: [u64; 16] = [
+        0xC, 0x5, 0x6, 0xB, 0x9, 0x0, 0xA, 0xD, 0x3, 0xE, 0xF, 0x8, 0x4, 0x7, 0x1, 0x2,
+    ];
+
+    fn point(bits: &[(usize, bool)], n: usize) -> VarSet {
+        let mut a = VarSet::empty(n);
+        for &(v, b) in bits {
+            if b {
+                a.insert(v);
+            }
+        }
+        a
+    }
+
+    #[test]
+    fn anf_matches_table() {
+        let ring = Ring::<DegLex>::new(4).into_ref();
+        let sbox = Sbox::new(PRESENT.to_vec(), 4);
+        let anf = sbox.anf(&ring, &[0, 1, 2, 3]);
+        assert_eq!(
+            "x_0*x_1*x_2 + x_0*x_1*x_3 + x_0*x_2*x_3 + x_1*x_3 + x_2*x_3 + x_1 + x_3",
+            anf[1].to_string()
+        );
+        for (x, &y) in PRESENT.iter().enumerate() {
+            let a = point(&(0..4).map(|i| (i, x >> i & 1 == 1)).collect::<Vec<_>>(), 4);
+            for (j, f) in anf.iter().enumerate() {
+                assert_eq!(y >> j & 1 == 1, f.eval(&a));
+            }
+        }
+    }
+
+    #[test]
+    fn present_relations() {
+        let ring = Ring::<DegLex>::new(8).into_ref();
+        let sbox = Sbox::new(PRESENT.to_vec(), 4);
+        let inputs = [0, 1, 2, 3];
+        let outputs = [4, 5, 6, 7];
+        let relations = sbox.implicit_relations(&ring, &inputs, &outputs, 2);
+        // 37 monomials of degree at most 2 on 16 points of full rank
+        assert_eq!(21, relations.len());
+        for (x, &y) in PRESENT.iter().enumerate() {
+            let bits: Vec<_> = (0..4)
+                .map(|i| (inputs[i], x >> i & 1 == 1))
+                .chain((0..4).map(|j| (outputs[j], y >> j & 1 == 1)))
+                .collect();
+            let a = point(&bits, 8);
+            assert!(relations.iter().all(|r| !r.eval(&a)));
+        }
+        let leads: BTreeSet<_> = relations.iter().map(|r| r.lm().clone()).collect();
+        assert_eq!(21, leads.len());
+    }
+
+    #[test]
+    fn aes_has_39_quadratic_relations() {
+        // inversion in GF(2^8) followed by the affine map
+        let mul = |mut a: u64, mut b: u64| {
+            let mut p = 0;
+            while b != 0 {
+                if b & 1 == 1 {
+                    p ^= a;
+                }
+                a <<= 1;
+                if a & 0x100 != 0 {
+                    a ^= 0x11b;
+                }
+                b >>= 1;
+            }
+            p
+        };
+        let table: Vec<u64> = (0..256)
+            .map(|x| {
+                let inv = (1..256).find(|&y| mul(x, y) == 1).unwrap_or(0);
+                let mut s = 0x63;
+                for i in 0..8 {
+                    let bit = [0, 4, 5, 6, 7]
+                        .iter()
+                        .fold(0, |b, k| b ^ inv >> ((i + k) % 8) & 1);
+                    s ^= bit << i;
+                }
+                s
+            })
+            .collect();
+        assert_eq!(0x63, table[0]);
+        assert_eq!(0x7c, table[1]);
+        let ring = Ring::<DegLex>::new(16).into_ref();
+        let sbox = Sbox::new(table, 8);
+        let inputs: Vec<_> = (0..8).collect();
+        let outputs: Vec<_> = (8..16).collect();
+        assert_eq!(
+            39,
+            sbox.implicit_relations(&ring, &inputs, &outputs, 2).len()
+        );
+    }
+}
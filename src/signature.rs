@@ -0,0 +1,376 @@
+use crate::{groebner::interreduce, mon::Monomial, order::MonomialOrdering, poly::Polynomial};
+use std::{cmp::Ordering, collections::BTreeMap};
+
+/// Counts of what happened to the J-pairs, summed over all generators.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SignatureStats {
+    pub jpairs: usize,
+    /// Discarded because the signature is divisible by the signature of a
+    /// known syzygy: a squared variable, Koszul, Boolean `(f + 1) * f = 0` or
+    /// found by reduction.
+    pub syzygy_criterion: usize,
+    /// Discarded because an element with a dividing signature already gives a
+    /// smaller leading monomial.
+    pub cover_criterion: usize,
+    /// Reduced, but an element with the same signature and leading monomial
+    /// already existed.
+    pub super_reducible: usize,
+    pub reductions: usize,
+    pub zero_reductions: usize,
+}
+
+impl SignatureStats {
+    /// Pairs thrown away without a reduction. A plain Buchberger run would
+    /// have reduced every one of them, almost always to zero.
+    pub fn avoided_reductions(&self) -> usize {
+        self.syzygy_criterion + self.cover_criterion
+    }
+}
+
+/// Compares the products `l[0] * l[1]` and `r[0] * r[1]` as monomials of
+/// `F_2[x]`, where a variable in both factors is squared: by degree, then
+/// lexicographically on the exponents. This is the signature order, whatever
+/// the ordering of the ring.
+fn cmp_products<T: MonomialOrdering>(l: [&Monomial<T>; 2], r: [&Monomial<T>; 2]) -> Ordering {
+    let words = |m: &Monomial<T>| m.vars().expect("signatures are nonzero").words().to_vec();
+    let (l0, l1, r0, r1) = (words(l[0]), words(l[1]), words(r[0]), words(r[1]));
+    let degree = |m: [&Monomial<T>; 2]| m[0].degree() + m[1].degree();
+    degree(l).cmp(&degree(r)).then_with(|| {
+        for w in 0..l0.len() {
+            let (ones_l, twos_l) = (l0[w] ^ l1[w], l0[w] & l1[w]);
+            let (ones_r, twos_r) = (r0[w] ^ r1[w], r0[w] & r1[w]);
+            let diff = (ones_l ^ ones_r) | (twos_l ^ twos_r);
+            if diff != 0 {
+                let bit = 1 << (63 - diff.leading_zeros());
+                let exponent =
+                    |ones: u64, twos: u64| 2 * (twos & bit != 0) as u8 + (ones & bit != 0) as u8;
+                return exponent(ones_l, twos_l).cmp(&exponent(ones_r, twos_r));
+            }
+        }
+        Ordering::Equal
+    })
+}
+
+fn cmp_signatures<T: MonomialOrdering>(a: &Monomial<T>, b: &Monomial<T>) -> Ordering {
+    let one = Monomial::one(a.ring().expect("signatures are nonzero"));
+    cmp_products([a, &one], [b, &one])
+}
+
+/// Largest term of `p` in the signature order.
+fn signature_lead<T: MonomialOrdering>(p: &Polynomial<T>) -> Monomial<T> {
+    p.terms()
+        .max_by(|a, b| cmp_signatures(a, b))
+        .expect("nonzero polynomial")
+        .clone()
+}
+
+/// Map key ordered by the signature order.
+struct Signature<T: MonomialOrdering>(Monomial<T>);
+
+impl<T: MonomialOrdering> PartialEq for Signature<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: MonomialOrdering> Eq for Signature<T> {}
+
+impl<T: MonomialOrdering> PartialOrd for Signature<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: MonomialOrdering> Ord for Signature<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_signatures(&self.0, &other.0)
+    }
+}
+
+/// `u * f = v` modulo the ideal of the previous generators, with `sig` the
+/// leading monomial of `u`.
+struct Labeled<T: MonomialOrdering> {
+    sig: Monomial<T>,
+    v: Polynomial<T>,
+}
+
+/// The multiple `t * elems[k]`. `lcm` is the leading monomial of `t * v`
+/// in `F_2[x]` when it is squarefree, which is not the case for field pairs.
+struct JPair<T: MonomialOrdering> {
+    k: usize,
+    t: Monomial<T>,
+    lcm: Option<Monomial<T>>,
+}
+
+struct Incremental<'b, T: MonomialOrdering> {
+    /// Reduced Gröbner basis of the generators handled so far.
+    prev: &'b [Polynomial<T>],
+    elems: Vec<Labeled<T>>,
+    /// Signatures of known syzygies.
+    syz: Vec<Monomial<T>>,
+    jpairs: BTreeMap<Signature<T>, Vec<JPair<T>>>,
+    stats: &'b mut SignatureStats,
+}
+
+impl<'b, T: MonomialOrdering> Incremental<'b, T> {
+    fn is_syzygy(&self, sig: &Monomial<T>) -> bool {
+        self.syz.iter().any(|s| s.divides(sig))
+    }
+
+    fn push_jpair(&mut self, jpair: JPair<T>) {
+        let base = &self.elems[jpair.k].sig;
+        // x^2 + x lies in the previous ideal, so a squared variable makes the
+        // signature one of a syzygy
+        if !jpair.t.vars().unwrap().is_disjoint(base.vars().unwrap()) {
+            self.stats.syzygy_criterion += 1;
+            return;
+        }
+        let sig = base * &jpair.t;
+        if self.is_syzygy(&sig) {
+            self.stats.syzygy_criterion += 1;
+        } else {
+            self.stats.jpairs += 1;
+            self.jpairs.entry(Signature(sig)).or_default().push(jpair);
+        }
+    }
+
+    /// J-pairs of the newest element with all others, with the previous
+    /// basis and with the field equations of the variables in its leading
+    /// monomial.
+    fn add(&mut self, elem: Labeled<T>) {
+        let n = self.elems.len();
+        let ring = elem.v.ring().clone();
+
+        // u * (v + 1) * f = (v + 1) * v = 0
+        let lead = signature_lead(&elem.v);
+        if lead.vars().unwrap().is_disjoint(elem.sig.vars().unwrap()) {
+            self.syz.push(&elem.sig * &lead);
+        }
+
+        let mut jpairs = Vec::new();
+        for (k, other) in self.elems.iter().enumerate() {
+            let lcm = elem.v.lm() * other.v.lm();
+            let t_n = &lcm / elem.v.lm();
+            let t_k = &lcm / other.v.lm();
+            let (k, t) = match cmp_products([&elem.sig, &t_n], [&other.sig, &t_k]) {
+                Ordering::Greater => (n, t_n),
+                Ordering::Less => (k, t_k),
+                Ordering::Equal => continue,
+            };
+            jpairs.push(JPair {
+                k,
+                t,
+                lcm: Some(lcm),
+            });
+        }
+        // the previous basis has signature zero
+        for g in self.prev {
+            let lcm = elem.v.lm() * g.lm();
+            jpairs.push(JPair {
+                k: n,
+                t: &lcm / elem.v.lm(),
+                lcm: Some(lcm),
+            });
+        }
+        for x in elem.v.lm().vars().unwrap().iter() {
+            jpairs.push(JPair {
+                k: n,
+                t: Monomial::from_variable(&ring, ring.var(x)),
+                lcm: None,
+            });
+        }
+        self.elems.push(elem);
+        for jpair in jpairs {
+            self.push_jpair(jpair);
+        }
+    }
+
+    fn covered(&self, sig: &Monomial<T>, lcm: &Monomial<T>) -> bool {
+        self.elems.iter().any(|e| {
+            e.sig.divides(sig) && {
+                let s = sig / &e.sig;
+                let lm = e.v.lm();
+                s.vars().unwrap().is_disjoint(lm.vars().unwrap()) && &(&s * lm) < lcm
+            }
+        })
+    }
+
+    /// Top reduction of `elem` by the previous basis and by the multiples of
+    /// elements whose signature stays below the one of `elem`. Returns `None`
+    /// if `elem` is super top-reducible.
+    fn regular_reduce(&mut self, mut elem: Labeled<T>) -> Option<Labeled<T>> {
+        let one = Monomial::one(elem.v.ring());
+        'outer: while !elem.v.is_zero() {
+            let m = elem.v.lm().clone();
+            if let Some(g) = self.prev.iter().find(|g| g.lm().divides(&m)) {
+                elem.v += g * (&m / g.lm());
+                continue;
+            }
+            // a J-pair is super top-reducible by the element it comes from, so
+            // only give up once no regular reducer is left
+            let mut super_reducible = false;
+            for e in &self.elems {
+                if e.v.lm().divides(&m) {
+                    let s = &m / e.v.lm();
+                    match cmp_products([&e.sig, &s], [&elem.sig, &one]) {
+                        Ordering::Less => {
+                            self.stats.reductions += 1;
+                            elem.v += &e.v * &s;
+                            continue 'outer;
+                        }
+                        Ordering::Equal => super_reducible = true,
+                        Ordering::Greater => {}
+                    }
+                }
+            }
+            if super_reducible {
+                self.stats.super_reducible += 1;
+                return None;
+            }
+            break;
+        }
+        Some(elem)
+    }
+
+    fn run(&mut self, f: Polynomial<T>) {
+        let ring = f.ring().clone();
+        self.add(Labeled {
+            sig: Monomial::one(&ring),
+            v: f,
+        });
+        while let Some((Signature(sig), mut group)) = self.jpairs.pop_first() {
+            // one pair of a signature covers the others if it leads to a
+            // smaller leading monomial, so try those first
+            group.sort_by(|a, b| (a.lcm.is_none(), &a.lcm).cmp(&(b.lcm.is_none(), &b.lcm)));
+            for jpair in group {
+                if self.is_syzygy(&sig) {
+                    self.stats.syzygy_criterion += 1;
+                    continue;
+                }
+                if let Some(lcm) = &jpair.lcm {
+                    if self.covered(&sig, lcm) {
+                        self.stats.cover_criterion += 1;
+                        continue;
+                    }
+                }
+                let elem = Labeled {
+                    v: &self.elems[jpair.k].v * &jpair.t,
+                    sig: sig.clone(),
+                };
+                if let Some(elem) = self.regular_reduce(elem) {
+                    if elem.v.is_zero() {
+                        self.stats.zero_reductions += 1;
+                        self.syz.push(elem.sig);
+                    } else {
+                        self.add(elem);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Keeps one element per minimal leading monomial, then interreduces.
+fn reduced_basis<T: MonomialOrdering>(mut polys: Vec<Polynomial<T>>) -> Vec<Polynomial<T>> {
+    polys.sort_by(|a, b| a.lm().cmp(b.lm()));
+    let mut minimal: Vec<Polynomial<T>> = Vec::new();
+    for p in polys {
+        if !minimal.iter().any(|g| g.lm().divides(p.lm())) {
+            minimal.push(p);
+        }
+    }
+    interreduce(minimal)
+}
+
+/// Reduced Gröbner basis like `groebner_basis`, computed with the
+/// incremental signature-based algorithm of Gao, Volny and Wang.
+///
+/// Generators are added one at a time. For the current generator `f` every
+/// element `v` stands for some `u * f` modulo the previous ideal, and the
+/// leading monomial of `u` is its signature. The computation is the one over
+/// `F_2[x]` with the field equations in the previous ideal: a signature
+/// picking up a squared variable belongs to a syzygy, and so do the leading
+/// monomials of the previous basis (Koszul) and `lm(u) * lm(v)` from the
+/// Boolean syzygy `(v + 1) * v = 0`. Signatures are compared by degree first,
+/// independently of the ordering of the ring.
+pub fn signature_basis<T: MonomialOrdering>(generators: &[Polynomial<T>]) -> Vec<Polynomial<T>> {
+    signature_basis_with_stats(generators).0
+}
+
+pub fn signature_basis_with_stats<T: MonomialOrdering>(
+    generators: &[Polynomial<T>],
+) -> (Vec<Polynomial<T>>, SignatureStats) {
+    let mut stats = SignatureStats::default();
+    let mut basis: Vec<Polynomial<T>> = Vec::new();
+    for f in generators {
+        let basis_refs: Vec<_> = basis.iter().collect();
+        let f = f.normal_form_refs(&basis_refs);
+        if f.is_zero() {
+            continue;
+        }
+        if f.lm().is_one() {
+            return (vec![f], stats);
+        }
+        let mut inc = Incremental {
+            prev: &basis,
+            elems: Vec::new(),
+            syz: basis.iter().map(signature_lead).collect(),
+            jpairs: BTreeMap::new(),
+            stats: &mut stats,
+        };
+        inc.run(f);
+        let new: Vec<_> = inc.elems.into_iter().map(|e| e.v).collect();
+        if new.iter().any(|v| v.lm().is_one()) {
+            let one = Polynomial::one(generators[0].ring());
+            return (vec![one], stats);
+        }
+        basis.extend(new);
+        basis = reduced_basis(basis);
+    }
+    (basis, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::signature_basis_with_stats;
+    use crate::{
+        groebner::groebner_basis,
+        order::{DegLex, Lex},
+        ring::Ring,
+        testing::variables,
+    };
+
+    #[test]
+    fn agrees_with_buchberger() {
+        let ring = Ring::<DegLex>::new(6).into_ref();
+        let x = variables(&ring);
+        let f = [
+            &x[0] * &x[1] + &x[2] * &x[3] + &x[4] + 1,
+            &x[1] * &x[2] + &x[0] * &x[5] + &x[3],
+            &x[3] * &x[4] + &x[1] * &x[3] + &x[2] + &x[5],
+            &x[0] * &x[4] + &x[2] * &x[5] + &x[1],
+        ];
+        let expected: Vec<_> = groebner_basis(&f).iter().map(|g| g.to_string()).collect();
+        let (gb, _) = signature_basis_with_stats(&f);
+        let gb: Vec<_> = gb.iter().map(|g| g.to_string()).collect();
+        assert_eq!(expected, gb);
+    }
+
+    #[test]
+    fn avoids_reductions() {
+        let ring = Ring::<Lex>::new(4).into_ref();
+        let x = variables(&ring);
+        // overdetermined: every product of two of the x_i + x_j
+        let mut f = Vec::new();
+        for i in 0..4 {
+            for j in i + 1..4 {
+                f.push((&x[i] + &x[j]) * (&x[i] + 1));
+            }
+        }
+        let (gb, stats) = signature_basis_with_stats(&f);
+        let expected: Vec<_> = groebner_basis(&f).iter().map(|g| g.to_string()).collect();
+        let gb: Vec<_> = gb.iter().map(|g| g.to_string()).collect();
+        assert_eq!(expected, gb);
+        assert!(stats.avoided_reductions() > 0);
+    }
+}
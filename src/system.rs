@@ -0,0 +1,157 @@
+use crate::{eval::Lanes, order::MonomialOrdering, poly::Polynomial, var::VarSet};
+use std::fmt::{self, Display};
+
+/// Polynomial `index` of a system became the constant one under a partial
+/// assignment, so the system has no solution extending it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Contradiction {
+    pub index: usize,
+}
+
+impl Display for Contradiction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "polynomial {} reduces to 1", self.index)
+    }
+}
+
+impl std::error::Error for Contradiction {}
+
+/// Applies a partial assignment to every polynomial and drops those that
+/// vanish.
+pub fn fix_system<T: MonomialOrdering>(
+    polys: &[Polynomial<T>],
+    assignment: &[(usize, bool)],
+) -> Result<Vec<Polynomial<T>>, Contradiction> {
+    let mut res = Vec::new();
+    for (index, p) in polys.iter().enumerate() {
+        let q = p.fix_many(assignment);
+        if q.lm().is_one() {
+            return Err(Contradiction { index });
+        }
+        if !q.is_zero() {
+            res.push(q);
+        }
+    }
+    Ok(res)
+}
+
+/// Values of all polynomials of a system at one assignment.
+pub fn eval_system<T: MonomialOrdering>(polys: &[Polynomial<T>], assignment: &VarSet) -> Vec<bool> {
+    polys.iter().map(|p| p.eval(assignment)).collect()
+}
+
+pub fn eval_system_batch<T: MonomialOrdering, L: Lanes>(
+    polys: &[Polynomial<T>],
+    vars: &[L],
+) -> Vec<L> {
+    polys.iter().map(|p| p.eval_batch(vars)).collect()
+}
+
+/// Whether every polynomial vanishes at `assignment`.
+pub fn is_solution<T: MonomialOrdering>(polys: &[Polynomial<T>], assignment: &VarSet) -> bool {
+    polys.iter().all(|p| !p.eval(assignment))
+}
+
+/// Lanes among the first `lanes` of the batch at which every polynomial
+/// vanishes; the unused lanes of a partial batch are never reported. Stops
+/// as soon as no lane is left, so most candidates cost one or two
+/// evaluations.
+pub fn solution_mask<T: MonomialOrdering, L: Lanes>(
+    polys: &[Polynomial<T>],
+    vars: &[L],
+    lanes: usize,
+) -> L {
+    let mut mask = !L::ZERO;
+    if lanes < L::WIDTH {
+        mask = L::ZERO;
+        for lane in 0..lanes {
+            mask.set(lane, true);
+        }
+    }
+    for p in polys {
+        mask = mask & !p.eval_batch(vars);
+        if mask == L::ZERO {
+            break;
+        }
+    }
+    mask
+}
+
+/// Variables occurring in some polynomial of the system.
+pub fn system_variables<T: MonomialOrdering>(polys: &[Polynomial<T>]) -> VarSet {
+    let mut set = VarSet::empty(polys.first().map_or(0, |p| p.ring().gens()));
+    for p in polys {
+        set.union_with(&p.variables());
+    }
+    set
+}
+
+/// Polynomials involving no variable outside `vars`, e.g. the key schedule
+/// equations for the set of key variables.
+pub fn polys_within<'p, T: MonomialOrdering>(
+    polys: &'p [Polynomial<T>],
+    vars: &VarSet,
+) -> Vec<&'p Polynomial<T>> {
+    polys
+        .iter()
+        .filter(|p| p.variables().is_subset(vars))
+        .collect()
+}
+
+/// Polynomials involving at least one variable of `vars`.
+pub fn polys_touching<'p, T: MonomialOrdering>(
+    polys: &'p [Polynomial<T>],
+    vars: &VarSet,
+) -> Vec<&'p Polynomial<T>> {
+    polys
+        .iter()
+        .filter(|p| !p.variables().is_disjoint(vars))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fix_system, is_solution, solution_mask, Contradiction};
+    use crate::{
+        eval::pack_assignments,
+        order::Lex,
+        ring::Ring,
+        testing::{assignments, variables},
+    };
+
+    #[test]
+    fn solutions_of_system() {
+        let n = 4;
+        let ring = Ring::<Lex>::new(n).into_ref();
+        let x = variables(&ring);
+        // x_0 = x_1 * x_2, x_3 = x_0 + 1
+        let f = [&x[0] + &x[1] * &x[2], &x[3] + &x[0] + 1];
+        let all: Vec<_> = assignments(n).collect();
+        let mask: u64 = solution_mask(&f, &pack_assignments(&all, n), all.len());
+        assert_eq!(4, mask.count_ones());
+        for (lane, a) in all.iter().enumerate() {
+            assert_eq!(is_solution(&f, a), mask >> lane & 1 == 1);
+        }
+        // the zero assignment solves g, so the padding lanes would too
+        let g = [&x[0] * &x[1], &x[2] + &x[3]];
+        let mask: u64 = solution_mask(&g, &pack_assignments(&all[1..], n), all.len() - 1);
+        assert_eq!(0, mask >> (all.len() - 1));
+        for (lane, a) in all[1..].iter().enumerate() {
+            assert_eq!(is_solution(&g, a), mask >> lane & 1 == 1);
+        }
+    }
+
+    #[test]
+    fn partial_assignment() {
+        let ring = Ring::<Lex>::new(3).into_ref();
+        let x = variables(&ring);
+        let f = [&x[0] * &x[1] + &x[2], &x[0] + &x[1], &x[1] * &x[2] + 1];
+        let fixed = fix_system(&f, &[(0, true), (1, true)]).unwrap();
+        let fixed: Vec<_> = fixed.iter().map(|p| p.to_string()).collect();
+        assert_eq!(vec!["x_2 + 1", "x_2 + 1"], fixed);
+        assert_eq!(
+            Some(Contradiction { index: 1 }),
+            fix_system(&f, &[(0, true), (1, false)]).err()
+        );
+    }
+}
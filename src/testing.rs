@@ -0,0 +1,58 @@
+use crate::{order::MonomialOrdering, poly::Polynomial, ring::RingRef, var::VarSet};
+
+/// The variables of `ring` as polynomials.
+pub(crate) fn variables<T: MonomialOrdering>(ring: &RingRef<T>) -> Vec<Polynomial<T>> {
+    (0..ring.gens())
+        .map(|i| Polynomial::from_variable(ring, ring.var(i)))
+        .collect()
+}
+
+/// The assignment of `n` variables in which variable `v` is bit `v` of
+/// `bits`.
+pub(crate) fn assignment(n: usize, bits: usize) -> VarSet {
+    let mut a = VarSet::empty(n);
+    for v in (0..n).filter(|v| bits >> v & 1 == 1) {
+        a.insert(v);
+    }
+    a
+}
+
+/// All assignments of `n` variables, the bits of `0..2^n` in turn.
+pub(crate) fn assignments(n: usize) -> impl Iterator<Item = VarSet> {
+    (0..1usize << n).map(move |bits| assignment(n, bits))
+}
+
+/// `count` sums of `terms` products of up to `degree` random variables each,
+/// plus 1 half of the time, from a xorshift generator.
+pub(crate) fn random_system<T: MonomialOrdering>(
+    ring: &RingRef<T>,
+    count: usize,
+    terms: usize,
+    degree: usize,
+    seed: u64,
+) -> Vec<Polynomial<T>> {
+    let x = variables(ring);
+    let mut state = seed;
+    let mut next = move |bound: usize| {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state as usize % bound
+    };
+    (0..count)
+        .map(|_| {
+            let mut p = Polynomial::zero(ring);
+            for _ in 0..terms {
+                let mut m = Polynomial::one(ring);
+                for _ in 0..1 + next(degree) {
+                    m = m * &x[next(x.len())];
+                }
+                p += m;
+            }
+            if next(2) == 1 {
+                p += 1;
+            }
+            p
+        })
+        .collect()
+}
@@ -1,6 +1,6 @@
 use std::cmp::Ordering;
-use std::fmt::{self, Display};
-#[derive(Debug, Clone, Copy)]
+use std::fmt::{self, Debug, Display};
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AssociatedVariableType {
     NoType,
     L(usize, usize, usize),
@@ -9,7 +9,7 @@ pub enum AssociatedVariableType {
     Y(usize, usize),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Variable {
     name: String,
     order: usize,
@@ -64,3 +64,132 @@ impl Display for Variable {
         write!(f, "{}", self.name)
     }
 }
+
+/// Number of `u64` words stored inline; rings with more variables spill to the heap.
+pub const INLINE_WORDS: usize = 4;
+
+/// A set of variable indices packed into 64-bit words.
+///
+/// Variable `i` lives in word `i / 64` at bit `63 - i % 64`, so lower indices
+/// occupy the more significant bits and comparing the word slices
+/// lexicographically as unsigned integers is the `Lex` order on the sets.
+/// All sets belonging to one ring have the same number of words.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum VarSet {
+    Inline([u64; INLINE_WORDS]),
+    Heap(Box<[u64]>),
+}
+
+impl VarSet {
+    /// An empty set able to hold variables `0..n`.
+    pub fn empty(n: usize) -> Self {
+        let words = n.div_ceil(64);
+        if words <= INLINE_WORDS {
+            VarSet::Inline([0; INLINE_WORDS])
+        } else {
+            VarSet::Heap(vec![0; words].into_boxed_slice())
+        }
+    }
+
+    fn bit(v: usize) -> (usize, u64) {
+        (v / 64, 1 << (63 - v % 64))
+    }
+
+    pub fn words(&self) -> &[u64] {
+        match self {
+            VarSet::Inline(words) => words,
+            VarSet::Heap(words) => words,
+        }
+    }
+
+    pub fn words_mut(&mut self) -> &mut [u64] {
+        match self {
+            VarSet::Inline(words) => words,
+            VarSet::Heap(words) => words,
+        }
+    }
+
+    pub fn insert(&mut self, v: usize) {
+        let (w, mask) = Self::bit(v);
+        self.words_mut()[w] |= mask;
+    }
+
+    pub fn remove(&mut self, v: usize) {
+        let (w, mask) = Self::bit(v);
+        self.words_mut()[w] &= !mask;
+    }
+
+    pub fn contains(&self, v: usize) -> bool {
+        let (w, mask) = Self::bit(v);
+        self.words().get(w).is_some_and(|word| word & mask != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words().iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words().iter().all(|&w| w == 0)
+    }
+
+    pub fn union_with(&mut self, other: &VarSet) {
+        for (a, b) in self.words_mut().iter_mut().zip(other.words()) {
+            *a |= b;
+        }
+    }
+
+    pub fn difference_with(&mut self, other: &VarSet) {
+        for (a, b) in self.words_mut().iter_mut().zip(other.words()) {
+            *a &= !b;
+        }
+    }
+
+    pub fn is_subset(&self, other: &VarSet) -> bool {
+        self.words()
+            .iter()
+            .zip(other.words())
+            .all(|(a, b)| a & !b == 0)
+    }
+
+    pub fn is_disjoint(&self, other: &VarSet) -> bool {
+        self.words()
+            .iter()
+            .zip(other.words())
+            .all(|(a, b)| a & b == 0)
+    }
+
+    /// Variable indices in increasing order.
+    pub fn iter(&self) -> VarSetIter<'_> {
+        let words = self.words();
+        VarSetIter {
+            words,
+            word: 0,
+            cur: words.first().copied().unwrap_or(0),
+        }
+    }
+}
+
+impl Debug for VarSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+pub struct VarSetIter<'a> {
+    words: &'a [u64],
+    word: usize,
+    cur: u64,
+}
+
+impl<'a> Iterator for VarSetIter<'a> {
+    type Item = usize;
+    fn next(&mut self) -> Option<usize> {
+        while self.cur == 0 {
+            self.word += 1;
+            self.cur = *self.words.get(self.word)?;
+        }
+        let offset = self.cur.leading_zeros() as usize;
+        self.cur &= !(1 << (63 - offset));
+        Some(self.word * 64 + offset)
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{
+    order::Lex,
+    poly::Polynomial,
+    ring::RingRef,
+    system::system_variables,
+    var::{VarSet, Variable},
+};
+use std::collections::BTreeMap;
+
+/// Values of all ring variables at one common zero.
+pub type Solution = BTreeMap<Variable, bool>;
+
+#[derive(Default)]
+pub struct SolutionOptions {
+    /// Stop after this many solutions.
+    pub limit: Option<usize>,
+}
+
+/// Called with every solution as `(variable, value)` pairs; returns whether
+/// to go on.
+type Visitor<'v> = dyn FnMut(&[(usize, bool)]) -> bool + 'v;
+
+/// Depth-first walk over the variables below `v` from the last one to
+/// `x_0`, leaving out those in `skip`. The elements of `levels[v]` have `x_v`
+/// as their largest variable, so once the later variables are set each of
+/// them is `a x_v + b` with constant `a` and `b`. Returns false once `visit`
+/// asks to stop.
+fn walk(
+    levels: &[Vec<&Polynomial<Lex>>],
+    skip: &VarSet,
+    v: usize,
+    assignment: &mut Vec<(usize, bool)>,
+    visit: &mut Visitor<'_>,
+) -> bool {
+    if v == 0 {
+        return visit(assignment);
+    }
+    let v = v - 1;
+    if skip.contains(v) {
+        return walk(levels, skip, v, assignment, visit);
+    }
+    let mut forced = None;
+    for p in &levels[v] {
+        let q = p.fix_many(assignment);
+        let linear = q.terms().any(|m| !m.is_one());
+        let constant = q.terms().any(|m| m.is_one());
+        match (linear, forced) {
+            (false, _) if constant => return true,
+            (false, _) => {}
+            (true, Some(value)) if value != constant => return true,
+            (true, _) => forced = Some(constant),
+        }
+    }
+    let values: &[bool] = match forced {
+        Some(false) => &[false],
+        Some(true) => &[true],
+        None => &[false, true],
+    };
+    for &value in values {
+        assignment.push((v, value));
+        let go_on = walk(levels, skip, v, assignment, visit);
+        assignment.pop();
+        if !go_on {
+            return false;
+        }
+    }
+    true
+}
+
+/// Visits the solutions on the variables of `ring` not in `skip`, which
+/// must occur in no element of `basis`.
+fn for_each_solution(
+    ring: &RingRef<Lex>,
+    basis: &[Polynomial<Lex>],
+    skip: &VarSet,
+    visit: &mut Visitor<'_>,
+) {
+    if basis.iter().any(|g| g.lm().is_one()) {
+        return;
+    }
+    let mut levels = vec![Vec::new(); ring.gens()];
+    for g in basis.iter().filter(|g| !g.is_zero()) {
+        // under Lex the leading monomial holds the largest variable
+        let v = g.lm().vars().unwrap().iter().next().unwrap();
+        levels[v].push(g);
+    }
+    walk(&levels, skip, ring.gens(), &mut Vec::new(), visit);
+}
+
+/// Common zeros over `ring` of a reduced `Lex` Gröbner basis, found by
+/// solving it triangularly for the last variable first and branching on the
+/// variables it leaves free. Since the field equations belong to the ideal,
+/// every partial solution extends, so no branch is a dead end.
+///
+/// Use `fglm` to get such a basis from one under a degree ordering.
+pub fn solutions(
+    ring: &RingRef<Lex>,
+    basis: &[Polynomial<Lex>],
+    options: &SolutionOptions,
+) -> Vec<Solution> {
+    let mut res = Vec::new();
+    if options.limit == Some(0) {
+        return res;
+    }
+    let skip = VarSet::empty(ring.gens());
+    for_each_solution(ring, basis, &skip, &mut |assignment| {
+        res.push(
+            assignment
+                .iter()
+                .map(|&(v, value)| (ring.var(v).clone(), value))
+                .collect(),
+        );
+        options.limit.is_none_or(|limit| res.len() < limit)
+    });
+    res
+}
+
+/// Number of common zeros over `ring` of a reduced `Lex` Gröbner basis,
+/// without building them. Variables absent from the basis just double it,
+/// so `None` is returned when the count does not fit in a `u128`.
+pub fn count_solutions(ring: &RingRef<Lex>, basis: &[Polynomial<Lex>]) -> Option<u128> {
+    let used = system_variables(basis);
+    let mut skip = VarSet::empty(ring.gens());
+    for v in (0..ring.gens()).filter(|&v| !used.contains(v)) {
+        skip.insert(v);
+    }
+    let mut count: u128 = 0;
+    for_each_solution(ring, basis, &skip, &mut |_| {
+        count += 1;
+        true
+    });
+    if count == 0 {
+        return Some(0);
+    }
+    1u128
+        .checked_shl(skip.len() as u32)
+        .and_then(|free| count.checked_mul(free))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count_solutions, solutions, SolutionOptions};
+    use crate::{
+        groebner::groebner_basis,
+        order::Lex,
+        ring::Ring,
+        system::is_solution,
+        testing::{assignments, variables},
+        var::VarSet,
+    };
+
+    #[test]
+    fn enumerates_variety() {
+        let n = 6;
+        let ring = Ring::<Lex>::new(n).into_ref();
+        let x = variables(&ring);
+        let f = [
+            &x[0] * &x[1] + &x[2] + &x[5],
+            &x[1] * &x[3] + &x[4] + 1,
+            &x[0] + &x[2] * &x[4] + &x[3],
+        ];
+        let mut expected: Vec<VarSet> = assignments(n).filter(|a| is_solution(&f, a)).collect();
+        expected.sort_by(|a, b| a.words().cmp(b.words()));
+        let basis = groebner_basis(&f);
+        assert_eq!(Some(expected.len() as u128), count_solutions(&ring, &basis));
+        let mut found: Vec<VarSet> = solutions(&ring, &basis, &SolutionOptions::default())
+            .iter()
+            .map(|s| {
+                assert_eq!(n, s.len());
+                let mut a = VarSet::empty(n);
+                for (v, _) in s.iter().filter(|(_, &value)| value) {
+                    a.insert(v.order() as usize);
+                }
+                a
+            })
+            .collect();
+        found.sort_by(|a, b| a.words().cmp(b.words()));
+        assert_eq!(expected, found);
+        let options = SolutionOptions { limit: Some(3) };
+        assert_eq!(3, solutions(&ring, &basis, &options).len());
+    }
+
+    #[test]
+    fn inconsistent_and_unconstrained() {
+        let ring = Ring::<Lex>::new(3).into_ref();
+        let x = variables(&ring);
+        let basis = groebner_basis(&[&x[0] * &x[1] + 1, x[0].clone() + 1 + &x[1]]);
+        assert_eq!(Some(0), count_solutions(&ring, &basis));
+        assert!(solutions(&ring, &basis, &SolutionOptions::default()).is_empty());
+        let basis = groebner_basis(&[&x[1] + &x[2]]);
+        assert_eq!(Some(4), count_solutions(&ring, &basis));
+        assert_eq!(
+            4,
+            solutions(&ring, &basis, &SolutionOptions::default()).len()
+        );
+        assert_eq!(Some(8), count_solutions(&ring, &[]));
+        assert_eq!(8, solutions(&ring, &[], &SolutionOptions::default()).len());
+
+        let wide = Ring::<Lex>::new(100).into_ref();
+        let x = variables(&wide);
+        assert_eq!(Some(1 << 99), count_solutions(&wide, &[&x[3] + &x[70] + 1]));
+        assert_eq!(Some(1 << 100), count_solutions(&wide, &[]));
+
+        let wider = Ring::<Lex>::new(130).into_ref();
+        let x = variables(&wider);
+        let basis = groebner_basis(&[&x[0] + 1, &x[1] + &x[2], &x[100] + &x[129]]);
+        assert_eq!(Some(1 << 127), count_solutions(&wider, &basis));
+        assert_eq!(None, count_solutions(&wider, &basis[1..]));
+        assert_eq!(None, count_solutions(&wider, &[]));
+        let basis = groebner_basis(&[&x[128] * &x[129] + 1, &x[128] + &x[129] + 1]);
+        assert_eq!(Some(0), count_solutions(&wider, &basis));
+    }
+}
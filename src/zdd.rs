@@ -0,0 +1,666 @@
+use crate::{
+    mon::Monomial, order::MonomialOrdering, poly::Polynomial, ring::RingRef, var::Variable,
+};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, MulAssign},
+};
+
+pub type NodeId = u32;
+
+/// The empty set of terms, i.e. the zero polynomial.
+pub const ZERO: NodeId = 0;
+/// The set containing only the empty term, i.e. the constant one.
+pub const ONE: NodeId = 1;
+
+const TERMINAL_VAR: u32 = u32::MAX;
+/// Marks a slot freed by garbage collection.
+const FREE_VAR: u32 = u32::MAX - 1;
+/// Live nodes below which no garbage is collected.
+const MIN_COLLECT: usize = 1 << 16;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct Node {
+    var: u32,
+    then_: NodeId,
+    else_: NodeId,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Op {
+    Add,
+    Mul,
+}
+
+/// Unique table and operation cache shared by all ZDD polynomials of a `Ring`.
+///
+/// A node `(v, then, else)` stands for `v * then + else`, where `then` and
+/// `else` only contain variables with a larger index than `v`. Nodes whose
+/// `then` branch is `ZERO` are never created, so every polynomial has exactly
+/// one representation and equality is a comparison of node ids.
+///
+/// Nodes are kept alive by the roots of the `ZddPolynomial`s, which hold a
+/// reference count on them. Ids handed out by the raw operations are only
+/// safe until the next `collect_garbage`.
+pub struct ZddManager {
+    nodes: Vec<Node>,
+    /// References from `ZddPolynomial`s to each node.
+    refs: Vec<u32>,
+    free: Vec<NodeId>,
+    unique: HashMap<Node, NodeId>,
+    cache: HashMap<(Op, NodeId, NodeId), NodeId>,
+    /// Live nodes at which `maybe_collect` collects next.
+    collect_at: usize,
+}
+
+impl Default for ZddManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ZddManager {
+    pub fn new() -> Self {
+        let terminal = |id| Node {
+            var: TERMINAL_VAR,
+            then_: id,
+            else_: id,
+        };
+        ZddManager {
+            nodes: vec![terminal(ZERO), terminal(ONE)],
+            refs: vec![0, 0],
+            free: Vec::new(),
+            unique: HashMap::new(),
+            cache: HashMap::new(),
+            collect_at: MIN_COLLECT,
+        }
+    }
+
+    /// Number of live nodes, terminals included.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    fn retain(&mut self, f: NodeId) {
+        self.refs[f as usize] += 1;
+    }
+
+    fn release(&mut self, f: NodeId) {
+        self.refs[f as usize] -= 1;
+    }
+
+    /// Frees every node not reachable from a `ZddPolynomial`, and the cache
+    /// entries mentioning one. The ids of the others stay the same.
+    pub fn collect_garbage(&mut self) {
+        let mut live = vec![false; self.nodes.len()];
+        live[ZERO as usize] = true;
+        live[ONE as usize] = true;
+        let mut stack: Vec<NodeId> = (0..self.nodes.len() as NodeId)
+            .filter(|&f| self.refs[f as usize] > 0)
+            .collect();
+        while let Some(f) = stack.pop() {
+            if !live[f as usize] {
+                live[f as usize] = true;
+                let (f1, f0) = self.branches(f);
+                stack.push(f1);
+                stack.push(f0);
+            }
+        }
+        for (f, node) in self.nodes.iter_mut().enumerate() {
+            if !live[f] && node.var != FREE_VAR {
+                self.unique.remove(node);
+                node.var = FREE_VAR;
+                self.free.push(f as NodeId);
+            }
+        }
+        self.cache
+            .retain(|&(_, f, g), r| live[f as usize] && live[g as usize] && live[*r as usize]);
+    }
+
+    /// Collects garbage once the live nodes have doubled since the last
+    /// time; the caller's operands must be held by `ZddPolynomial`s.
+    fn maybe_collect(&mut self) {
+        if self.node_count() >= self.collect_at {
+            self.collect_garbage();
+            self.collect_at = (2 * self.node_count()).max(MIN_COLLECT);
+        }
+    }
+
+    pub fn clear_cache(&mut self) {
+        self.cache.clear();
+    }
+
+    fn top(&self, f: NodeId) -> u32 {
+        self.nodes[f as usize].var
+    }
+
+    fn branches(&self, f: NodeId) -> (NodeId, NodeId) {
+        let n = &self.nodes[f as usize];
+        (n.then_, n.else_)
+    }
+
+    fn node(&mut self, var: u32, then_: NodeId, else_: NodeId) -> NodeId {
+        if then_ == ZERO {
+            return else_;
+        }
+        let node = Node { var, then_, else_ };
+        if let Some(&id) = self.unique.get(&node) {
+            return id;
+        }
+        let id = match self.free.pop() {
+            Some(id) => {
+                self.nodes[id as usize] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.refs.push(0);
+                (self.nodes.len() - 1) as NodeId
+            }
+        };
+        self.unique.insert(node, id);
+        id
+    }
+
+    /// Builds the single term `x_{vars[0]} * ... * x_{vars[k]}`.
+    pub fn term<I: IntoIterator<Item = usize>>(&mut self, vars: I) -> NodeId {
+        let mut vars: Vec<_> = vars.into_iter().map(|v| v as u32).collect();
+        vars.sort_unstable();
+        vars.dedup();
+        vars.iter()
+            .rev()
+            .fold(ONE, |acc, &v| self.node(v, acc, ZERO))
+    }
+
+    pub fn add(&mut self, f: NodeId, g: NodeId) -> NodeId {
+        if f == ZERO {
+            return g;
+        }
+        if g == ZERO {
+            return f;
+        }
+        if f == g {
+            return ZERO;
+        }
+        let key = (Op::Add, f.min(g), f.max(g));
+        if let Some(&r) = self.cache.get(&key) {
+            return r;
+        }
+        let (top_f, top_g) = (self.top(f), self.top(g));
+        let res = if top_f == top_g {
+            // both are non-terminal here, the terminals were handled above
+            let (f1, f0) = self.branches(f);
+            let (g1, g0) = self.branches(g);
+            let then_ = self.add(f1, g1);
+            let else_ = self.add(f0, g0);
+            self.node(top_f, then_, else_)
+        } else if top_f < top_g {
+            let (f1, f0) = self.branches(f);
+            let else_ = self.add(f0, g);
+            self.node(top_f, f1, else_)
+        } else {
+            let (g1, g0) = self.branches(g);
+            let else_ = self.add(f, g0);
+            self.node(top_g, g1, else_)
+        };
+        self.cache.insert(key, res);
+        res
+    }
+
+    pub fn mul(&mut self, f: NodeId, g: NodeId) -> NodeId {
+        if f == ZERO || g == ZERO {
+            return ZERO;
+        }
+        if f == ONE {
+            return g;
+        }
+        if g == ONE || f == g {
+            return f;
+        }
+        let key = (Op::Mul, f.min(g), f.max(g));
+        if let Some(&r) = self.cache.get(&key) {
+            return r;
+        }
+        let (top_f, top_g) = (self.top(f), self.top(g));
+        let res = if top_f == top_g {
+            // (v*f1 + f0) * (v*g1 + g0) = v*((f0 + f1)*(g0 + g1) + f0*g0) + f0*g0
+            let (f1, f0) = self.branches(f);
+            let (g1, g0) = self.branches(g);
+            let low = self.mul(f0, g0);
+            let f_sum = self.add(f0, f1);
+            let g_sum = self.add(g0, g1);
+            let high = self.mul(f_sum, g_sum);
+            let then_ = self.add(high, low);
+            self.node(top_f, then_, low)
+        } else {
+            let (f, g) = if top_f < top_g { (f, g) } else { (g, f) };
+            let (f1, f0) = self.branches(f);
+            let then_ = self.mul(f1, g);
+            let else_ = self.mul(f0, g);
+            self.node(self.top(f), then_, else_)
+        };
+        self.cache.insert(key, res);
+        res
+    }
+
+    /// Number of terms of the polynomial rooted at `f`.
+    pub fn count(&self, f: NodeId) -> usize {
+        fn count_rec(m: &ZddManager, f: NodeId, memo: &mut HashMap<NodeId, usize>) -> usize {
+            match f {
+                ZERO => 0,
+                ONE => 1,
+                _ => {
+                    if let Some(&c) = memo.get(&f) {
+                        return c;
+                    }
+                    let (f1, f0) = m.branches(f);
+                    let c = count_rec(m, f1, memo) + count_rec(m, f0, memo);
+                    memo.insert(f, c);
+                    c
+                }
+            }
+        }
+        count_rec(self, f, &mut HashMap::new())
+    }
+
+    /// Number of inner nodes reachable from `f`.
+    pub fn size(&self, f: NodeId) -> usize {
+        let mut seen = std::collections::HashSet::new();
+        let mut stack = vec![f];
+        while let Some(n) = stack.pop() {
+            if n > ONE && seen.insert(n) {
+                let (f1, f0) = self.branches(n);
+                stack.push(f1);
+                stack.push(f0);
+            }
+        }
+        seen.len()
+    }
+
+    /// Largest term of `f` under the ordering of `ring`. A monomial ordering
+    /// is kept by multiplying with a variable, so the largest term of
+    /// `v * f1 + f0` is the larger of `v` times that of `f1` and that of `f0`.
+    fn lm<T: MonomialOrdering>(
+        &self,
+        ring: &RingRef<T>,
+        f: NodeId,
+        memo: &mut HashMap<NodeId, Monomial<T>>,
+    ) -> Monomial<T> {
+        match f {
+            ZERO => Monomial::Zero,
+            ONE => Monomial::one(ring),
+            _ => {
+                if let Some(m) = memo.get(&f) {
+                    return m.clone();
+                }
+                let (f1, f0) = self.branches(f);
+                let high = self.lm(ring, f1, memo) * ring.var(self.top(f) as usize);
+                let m = high.max(self.lm(ring, f0, memo));
+                memo.insert(f, m.clone());
+                m
+            }
+        }
+    }
+
+    /// All terms of `f` as sorted lists of variable indices.
+    pub fn terms(&self, f: NodeId) -> Vec<Vec<usize>> {
+        let mut terms = Vec::new();
+        let mut path = Vec::new();
+        self.collect_terms(f, &mut path, &mut terms);
+        terms
+    }
+
+    fn collect_terms(&self, f: NodeId, path: &mut Vec<usize>, terms: &mut Vec<Vec<usize>>) {
+        match f {
+            ZERO => {}
+            ONE => terms.push(path.clone()),
+            _ => {
+                let (f1, f0) = self.branches(f);
+                path.push(self.top(f) as usize);
+                self.collect_terms(f1, path, terms);
+                path.pop();
+                self.collect_terms(f0, path, terms);
+            }
+        }
+    }
+}
+
+/// A Boolean polynomial stored as a node in the ZDD of its ring.
+///
+/// Structurally equal subpolynomials are stored once per `Ring`, which keeps
+/// large systems with many shared terms compact. Cloning is cheap.
+pub struct ZddPolynomial<T: MonomialOrdering> {
+    root: NodeId,
+    ring: RingRef<T>,
+}
+
+impl<T: MonomialOrdering> Clone for ZddPolynomial<T> {
+    fn clone(&self) -> Self {
+        ZddPolynomial::with_root(&self.ring, self.root)
+    }
+}
+
+impl<T: MonomialOrdering> Drop for ZddPolynomial<T> {
+    fn drop(&mut self) {
+        self.ring.zdd().release(self.root);
+    }
+}
+
+impl<T: MonomialOrdering> ZddPolynomial<T> {
+    fn with_root(ring: &RingRef<T>, root: NodeId) -> Self {
+        ring.zdd().retain(root);
+        ZddPolynomial {
+            root,
+            ring: ring.clone(),
+        }
+    }
+
+    /// Replaces the root by `op` of it, collecting garbage first if due.
+    fn update(&mut self, op: impl FnOnce(&mut ZddManager, NodeId) -> NodeId) {
+        let mut zdd = self.ring.zdd();
+        zdd.maybe_collect();
+        let root = op(&mut zdd, self.root);
+        zdd.retain(root);
+        zdd.release(self.root);
+        self.root = root;
+    }
+
+    pub fn new(ring: &RingRef<T>) -> Self {
+        ZddPolynomial::zero(ring)
+    }
+    pub fn zero(ring: &RingRef<T>) -> Self {
+        ZddPolynomial::with_root(ring, ZERO)
+    }
+    pub fn one(ring: &RingRef<T>) -> Self {
+        ZddPolynomial::with_root(ring, ONE)
+    }
+
+    pub fn from_variable(ring: &RingRef<T>, v: &Variable) -> Self {
+        let mut p = ZddPolynomial::zero(ring);
+        p.update(|zdd, _| zdd.term(std::iter::once(v.order() as usize)));
+        p
+    }
+
+    pub fn from_monomial(ring: &RingRef<T>, m: &Monomial<T>) -> Self {
+        let mut p = ZddPolynomial::zero(ring);
+        if let Some(vars) = m.vars() {
+            p.update(|zdd, _| zdd.term(vars.iter()));
+        }
+        p
+    }
+
+    pub fn from_polynomial(p: &Polynomial<T>) -> Self {
+        let mut res = ZddPolynomial::zero(p.ring());
+        res.update(|zdd, _| {
+            let mut root = ZERO;
+            for m in p.terms() {
+                if let Some(vars) = m.vars() {
+                    let t = zdd.term(vars.iter());
+                    root = zdd.add(root, t);
+                }
+            }
+            root
+        });
+        res
+    }
+
+    pub fn to_polynomial(&self) -> Polynomial<T> {
+        let terms = self.ring.zdd().terms(self.root);
+        let mut p = Polynomial::zero(&self.ring);
+        for vars in terms {
+            let m = vars
+                .into_iter()
+                .fold(Monomial::one(&self.ring), |m, v| m * self.ring.var(v));
+            p += &m;
+        }
+        p
+    }
+
+    pub fn ring(&self) -> &RingRef<T> {
+        &self.ring
+    }
+
+    pub fn root(&self) -> NodeId {
+        self.root
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.root == ZERO
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.root == ONE
+    }
+
+    /// Number of terms.
+    pub fn len(&self) -> usize {
+        self.ring.zdd().count(self.root)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.is_zero()
+    }
+
+    /// Number of diagram nodes used by this polynomial.
+    pub fn node_count(&self) -> usize {
+        self.ring.zdd().size(self.root)
+    }
+
+    /// Leading monomial, found in one pass over the diagram.
+    pub fn lm(&self) -> Monomial<T> {
+        self.ring
+            .zdd()
+            .lm(&self.ring, self.root, &mut HashMap::new())
+    }
+}
+
+impl<T: MonomialOrdering> From<&Polynomial<T>> for ZddPolynomial<T> {
+    fn from(p: &Polynomial<T>) -> Self {
+        ZddPolynomial::from_polynomial(p)
+    }
+}
+
+impl<T: MonomialOrdering> From<&ZddPolynomial<T>> for Polynomial<T> {
+    fn from(p: &ZddPolynomial<T>) -> Self {
+        p.to_polynomial()
+    }
+}
+
+impl<T: MonomialOrdering> PartialEq for ZddPolynomial<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.root == other.root
+    }
+}
+
+impl<T: MonomialOrdering> Eq for ZddPolynomial<T> {}
+
+impl<T: MonomialOrdering> Display for ZddPolynomial<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_polynomial())
+    }
+}
+
+impl<T: MonomialOrdering> AddAssign<&ZddPolynomial<T>> for ZddPolynomial<T> {
+    fn add_assign(&mut self, rhs: &Self) {
+        self.update(|zdd, root| zdd.add(root, rhs.root));
+    }
+}
+
+impl<T: MonomialOrdering> AddAssign for ZddPolynomial<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.add_assign(&rhs);
+    }
+}
+
+impl<T: MonomialOrdering> AddAssign<u64> for ZddPolynomial<T> {
+    fn add_assign(&mut self, rhs: u64) {
+        if rhs % 2 == 1 {
+            self.update(|zdd, root| zdd.add(root, ONE));
+        }
+    }
+}
+
+impl<T: MonomialOrdering> Add for ZddPolynomial<T> {
+    type Output = ZddPolynomial<T>;
+    fn add(mut self, rhs: Self) -> Self {
+        self.add_assign(&rhs);
+        self
+    }
+}
+
+impl<T: MonomialOrdering> Add<&ZddPolynomial<T>> for ZddPolynomial<T> {
+    type Output = ZddPolynomial<T>;
+    fn add(mut self, rhs: &Self) -> Self {
+        self.add_assign(rhs);
+        self
+    }
+}
+
+impl<T: MonomialOrdering> Add<&ZddPolynomial<T>> for &ZddPolynomial<T> {
+    type Output = ZddPolynomial<T>;
+    fn add(self, rhs: &ZddPolynomial<T>) -> ZddPolynomial<T> {
+        self.clone() + rhs
+    }
+}
+
+impl<T: MonomialOrdering> Add<u64> for ZddPolynomial<T> {
+    type Output = ZddPolynomial<T>;
+    fn add(mut self, rhs: u64) -> Self {
+        self.add_assign(rhs);
+        self
+    }
+}
+
+impl<T: MonomialOrdering> Add<u64> for &ZddPolynomial<T> {
+    type Output = ZddPolynomial<T>;
+    fn add(self, rhs: u64) -> ZddPolynomial<T> {
+        self.clone() + rhs
+    }
+}
+
+impl<T: MonomialOrdering> MulAssign<&ZddPolynomial<T>> for ZddPolynomial<T> {
+    fn mul_assign(&mut self, rhs: &Self) {
+        self.update(|zdd, root| zdd.mul(root, rhs.root));
+    }
+}
+
+impl<T: MonomialOrdering> MulAssign for ZddPolynomial<T> {
+    fn mul_assign(&mut self, rhs: Self) {
+        self.mul_assign(&rhs);
+    }
+}
+
+impl<T: MonomialOrdering> Mul for ZddPolynomial<T> {
+    type Output = ZddPolynomial<T>;
+    fn mul(mut self, rhs: Self) -> Self {
+        self.mul_assign(&rhs);
+        self
+    }
+}
+
+impl<T: MonomialOrdering> Mul<&ZddPolynomial<T>> for ZddPolynomial<T> {
+    type Output = ZddPolynomial<T>;
+    fn mul(mut self, rhs: &Self) -> Self {
+        self.mul_assign(rhs);
+        self
+    }
+}
+
+impl<T: MonomialOrdering> Mul<&ZddPolynomial<T>> for &ZddPolynomial<T> {
+    type Output = ZddPolynomial<T>;
+    fn mul(self, rhs: &ZddPolynomial<T>) -> ZddPolynomial<T> {
+        self.clone() * rhs
+    }
+}
+
+impl<T: MonomialOrdering> Mul<&Monomial<T>> for &ZddPolynomial<T> {
+    type Output = ZddPolynomial<T>;
+    fn mul(self, rhs: &Monomial<T>) -> ZddPolynomial<T> {
+        self * &ZddPolynomial::from_monomial(&self.ring, rhs)
+    }
+}
+
+impl<T: MonomialOrdering> Mul<&Monomial<T>> for ZddPolynomial<T> {
+    type Output = ZddPolynomial<T>;
+    fn mul(self, rhs: &Monomial<T>) -> ZddPolynomial<T> {
+        (&self).mul(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ZddPolynomial;
+    use crate::{
+        order::{DegLex, Lex},
+        ring::Ring,
+        testing::variables,
+    };
+
+    #[test]
+    fn mul_poly() {
+        let ring = Ring::<Lex>::new(8);
+        let ring = &ring.into_ref();
+        let x: Vec<_> = (0..6)
+            .map(|i| ZddPolynomial::from_variable(ring, ring.var(i)))
+            .collect();
+        let p1 = &x[0] + &x[1];
+        let p2 = &x[2] + &x[3];
+        let p3 = &x[3] + &x[5];
+        assert_eq!(
+            "x_0*x_2*x_3 + x_0*x_2*x_5 + x_0*x_3*x_5 + x_0*x_5 + x_1*x_2*x_3 + x_1*x_2*x_5 + x_1*x_3*x_5 + x_1*x_5 + x_2*x_3 + x_2*x_5 + x_3*x_5 + x_5",
+            ((p1 + 1) * (p2 + 1) * p3).to_string()
+        );
+    }
+
+    #[test]
+    fn matches_polynomial() {
+        let ring = Ring::<DegLex>::new(5);
+        let ring = &ring.into_ref();
+        let x = variables(ring);
+        let p = (&x[0] * &x[1] + &x[2]) * (&x[1] + &x[3] + 1) + &x[4];
+        let q = &x[0] * &x[4] + &x[1] * &x[2] * &x[3] + 1;
+        let z = ZddPolynomial::from(&p) * ZddPolynomial::from(&q);
+        assert_eq!((&p * &q).to_string(), z.to_string());
+        assert_eq!(p.lm(), &ZddPolynomial::from(&p).lm());
+        assert_eq!(ZddPolynomial::from(&p).len(), p.len());
+    }
+
+    #[test]
+    fn shared_nodes() {
+        let ring = Ring::<Lex>::new(4);
+        let ring = &ring.into_ref();
+        let x: Vec<_> = (0..4)
+            .map(|i| ZddPolynomial::from_variable(ring, ring.var(i)))
+            .collect();
+        let a = (&x[0] + &x[1]) * &x[2];
+        let b = &x[0] * &x[2] + &x[1] * &x[2];
+        assert!(a == b);
+        assert!((a.clone() + b).is_zero());
+        assert!((&a * &a) == a);
+    }
+
+    #[test]
+    fn garbage_is_collected() {
+        let ring = Ring::<DegLex>::new(12);
+        let ring = &ring.into_ref();
+        let x: Vec<_> = (0..12)
+            .map(|i| ZddPolynomial::from_variable(ring, ring.var(i)))
+            .collect();
+        let kept = (&x[0] + &x[1] + 1) * (&x[2] + &x[3]);
+        let expected = kept.to_string();
+        for i in 0..12 {
+            let _ = x
+                .iter()
+                .skip(i)
+                .fold(ZddPolynomial::one(ring), |p, y| p * (y + 1));
+        }
+        let before = ring.zdd().node_count();
+        ring.zdd().collect_garbage();
+        assert!(ring.zdd().node_count() < before);
+        assert_eq!(expected, kept.to_string());
+        let again = (&x[0] + &x[1] + 1) * (&x[2] + &x[3]);
+        assert!(again == kept);
+        assert_eq!(kept.to_polynomial().lm(), &kept.lm());
+    }
+}